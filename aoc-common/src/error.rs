@@ -0,0 +1,154 @@
+use crate::InputError;
+use std::fmt;
+use std::io;
+use std::process::ExitCode;
+
+/// Error of any solver. Every kind of error ends the solver with its own
+/// exit code:
+///
+/// | Code | Error        | Meaning                                          |
+/// |------|--------------|--------------------------------------------------|
+/// | 0    | -            | success                                          |
+/// | 1    | `Check`      | check done by the runner failed, e.g. a wrong    |
+/// |      |              | answer or a benchmark regression                 |
+/// | 2    | `Usage`      | invalid command line arguments                   |
+/// | 3    | `Io`         | input or other file can't be read or written     |
+/// | 4    | `Parse`      | input has invalid format                         |
+/// | 5    | `Validation` | input is well-formed, but breaks a puzzle rule   |
+/// | 6    | `NoSolution` | input is valid, but the puzzle has no solution   |
+///
+/// Code 101 is left for panics, which are bugs of the solver.
+#[derive(Debug)]
+pub enum Error {
+    Check(String),
+    Usage(String),
+    Io { context: String, error: io::Error },
+    Parse(String),
+    Validation(String),
+    NoSolution(String),
+}
+
+impl Error {
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Error::Check(_) => 1,
+            Error::Usage(_) => 2,
+            Error::Io { .. } => 3,
+            Error::Parse(_) => 4,
+            Error::Validation(_) => 5,
+            Error::NoSolution(_) => 6,
+        }
+    }
+
+    /// I/O error with a description of what was done when it happened.
+    pub fn io(context: impl Into<String>, error: io::Error) -> Self {
+        Error::Io {
+            context: context.into(),
+            error,
+        }
+    }
+}
+
+/// Runs the solver and converts its result into the exit code of the
+/// process. The error is printed to stderr.
+pub fn run<F>(solve: F) -> ExitCode
+where
+    F: FnOnce() -> Result<(), Error>,
+{
+    match solve() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::from(e.exit_code())
+        }
+    }
+}
+
+impl From<InputError> for Error {
+    fn from(error: InputError) -> Self {
+        match error {
+            InputError::Usage(usage) => Error::Usage(usage),
+            // Input which isn't UTF-8 can be read, but it isn't a valid text.
+            InputError::Io { source, error } if error.kind() == io::ErrorKind::InvalidData => {
+                Error::Parse(format!("{}, {}", source, error))
+            }
+            InputError::Io { source, error } => {
+                Error::io(format!("unable to read {}", source), error)
+            }
+            error @ (InputError::Parse { .. } | InputError::Ragged { .. }) => {
+                Error::Parse(error.to_string())
+            }
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Check(message) => write!(f, "{}", message),
+            Error::Usage(usage) => write!(f, "{}", usage),
+            Error::Io { context, error } => write!(f, "{}, {}", context, error),
+            Error::Parse(message) => write!(f, "invalid input, {}", message),
+            Error::Validation(message) => write!(f, "invalid input, {}", message),
+            Error::NoSolution(message) => write!(f, "no solution, {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::Error;
+    use crate::Source;
+    use std::collections::HashSet;
+    use std::io;
+
+    #[test]
+    fn distinct_exit_codes() {
+        let errors = [
+            Error::Check(String::new()),
+            Error::Usage(String::new()),
+            Error::io("", io::Error::from(io::ErrorKind::NotFound)),
+            Error::Parse(String::new()),
+            Error::Validation(String::new()),
+            Error::NoSolution(String::new()),
+        ];
+        let exit_codes = errors.iter().map(Error::exit_code).collect::<HashSet<u8>>();
+
+        assert_eq!(exit_codes.len(), errors.len());
+        assert!(!exit_codes.contains(&0));
+        assert!(!exit_codes.contains(&101));
+    }
+
+    #[test]
+    fn input_errors() {
+        let error = Error::from(
+            Source::from_argument("does-not-exist.dat")
+                .text()
+                .unwrap_err(),
+        );
+        assert_eq!(error.exit_code(), 3);
+        assert!(error
+            .to_string()
+            .starts_with("unable to read does-not-exist.dat, "));
+
+        let error = Error::from(
+            Source::Inline(String::from("1\nx\n"))
+                .integers::<u32>()
+                .unwrap_err(),
+        );
+        assert_eq!(error.exit_code(), 4);
+        assert_eq!(
+            error.to_string(),
+            "invalid input, inline input, line 2: unable to parse 'x', invalid digit found in string"
+        );
+    }
+}
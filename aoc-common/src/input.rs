@@ -0,0 +1,358 @@
+use std::env;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Cursor, Read};
+use std::ops::RangeBounds;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Explanation of the `[input]` argument, added to every usage message.
+const INPUT_USAGE: &str = "[input] is a file name, `-` for standard input or `--inline` \
+                           followed by the data itself";
+
+/// Place where the puzzle input is read from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    File(PathBuf),
+    /// Standard input. It can be read only once.
+    Stdin,
+    Inline(String),
+}
+
+/// Command line arguments of a solver: the input, followed by arguments
+/// specific to the solver.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Args {
+    pub input: Source,
+    pub rest: Vec<String>,
+}
+
+/// Character grid with rows of the same width.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Grid {
+    rows: Vec<Vec<char>>,
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    /// Arguments don't match the usage of the solver.
+    Usage(String),
+    Io {
+        source: String,
+        error: io::Error,
+    },
+    /// Line which can't be parsed, numbered from 1.
+    Parse {
+        source: String,
+        line: usize,
+        value: String,
+        message: String,
+    },
+    /// Row of a grid with a different width than the first row, numbered
+    /// from 1.
+    Ragged {
+        source: String,
+        line: usize,
+        width: usize,
+        expected: usize,
+    },
+}
+
+impl Args {
+    /// Reads arguments of the running binary. `extra` is the allowed number
+    /// of arguments after the input.
+    pub fn from_env<R: RangeBounds<usize>>(usage: &str, extra: R) -> Result<Args, InputError> {
+        // First argument is name of binary file.
+        Args::parse(env::args().skip(1), usage, extra)
+    }
+
+    pub fn parse<I, R>(args: I, usage: &str, extra: R) -> Result<Args, InputError>
+    where
+        I: IntoIterator<Item = String>,
+        R: RangeBounds<usize>,
+    {
+        let usage_error = || InputError::Usage(format!("{}\n{}", usage, INPUT_USAGE));
+        let mut args = args.into_iter();
+        let input = match args.next().as_deref() {
+            Some("--inline") => Source::Inline(args.next().ok_or_else(usage_error)?),
+            Some(argument) => Source::from_argument(argument),
+            None => return Err(usage_error()),
+        };
+        let rest = args.collect::<Vec<String>>();
+        if !extra.contains(&rest.len()) {
+            return Err(usage_error());
+        }
+        Ok(Args { input, rest })
+    }
+}
+
+impl Source {
+    /// Path of a file, or `-` for standard input.
+    pub fn from_argument(argument: &str) -> Source {
+        if argument == "-" {
+            Source::Stdin
+        } else {
+            Source::File(PathBuf::from(argument))
+        }
+    }
+
+    pub fn reader(&self) -> Result<Box<dyn BufRead>, InputError> {
+        match self {
+            Source::File(path) => match File::open(path) {
+                Ok(file) => Ok(Box::new(BufReader::new(file))),
+                Err(error) => Err(self.io_error(error)),
+            },
+            Source::Stdin => Ok(Box::new(io::stdin().lock())),
+            Source::Inline(text) => Ok(Box::new(Cursor::new(text.clone().into_bytes()))),
+        }
+    }
+
+    /// Whole input as a single string.
+    pub fn text(&self) -> Result<String, InputError> {
+        if let Source::Inline(text) = self {
+            return Ok(text.clone());
+        }
+        let mut text = String::new();
+        self.reader()?
+            .read_to_string(&mut text)
+            .map_err(|error| self.io_error(error))?;
+        Ok(text)
+    }
+
+    /// All lines of the input, without line endings.
+    pub fn lines(&self) -> Result<Vec<String>, InputError> {
+        Ok(self.text()?.lines().map(String::from).collect())
+    }
+
+    /// Single number in every line. Blank lines are skipped, any other line
+    /// which isn't a number is an error.
+    pub fn integers<T>(&self) -> Result<Vec<T>, InputError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let mut integers = Vec::new();
+        for (i, line) in self.lines()?.into_iter().enumerate() {
+            let value = line.trim();
+            if value.is_empty() {
+                continue;
+            }
+            let integer = value
+                .parse::<T>()
+                .map_err(|e| self.parse_error(i + 1, value, e.to_string()))?;
+            integers.push(integer);
+        }
+        Ok(integers)
+    }
+
+    /// Groups of lines separated by blank lines, also lines with whitespaces
+    /// only. Empty groups are skipped.
+    pub fn records(&self) -> Result<Vec<Vec<String>>, InputError> {
+        let mut records = Vec::new();
+        let mut record = Vec::new();
+        for line in self.lines()? {
+            if line.trim().is_empty() {
+                if !record.is_empty() {
+                    records.push(std::mem::take(&mut record));
+                }
+            } else {
+                record.push(line);
+            }
+        }
+        if !record.is_empty() {
+            records.push(record);
+        }
+        Ok(records)
+    }
+
+    /// Lines of the input as a grid of characters. Blank lines at the end of
+    /// the input are not part of the grid.
+    pub fn grid(&self) -> Result<Grid, InputError> {
+        let mut lines = self.lines()?;
+        while lines.last().is_some_and(|line| line.is_empty()) {
+            lines.pop();
+        }
+
+        let mut rows: Vec<Vec<char>> = Vec::with_capacity(lines.len());
+        for (i, line) in lines.into_iter().enumerate() {
+            let row = line.chars().collect::<Vec<char>>();
+            if let Some(first_row) = rows.first() {
+                if row.len() != first_row.len() {
+                    return Err(InputError::Ragged {
+                        source: self.to_string(),
+                        line: i + 1,
+                        width: row.len(),
+                        expected: first_row.len(),
+                    });
+                }
+            }
+            rows.push(row);
+        }
+        Ok(Grid { rows })
+    }
+
+    fn io_error(&self, error: io::Error) -> InputError {
+        InputError::Io {
+            source: self.to_string(),
+            error,
+        }
+    }
+
+    fn parse_error(&self, line: usize, value: &str, message: String) -> InputError {
+        InputError::Parse {
+            source: self.to_string(),
+            line,
+            value: value.to_string(),
+            message,
+        }
+    }
+}
+
+impl Grid {
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn width(&self) -> usize {
+        self.rows.first().map_or(0, Vec::len)
+    }
+
+    /// Character at the given position, numbered from 0.
+    pub fn get(&self, row: usize, column: usize) -> Option<char> {
+        self.rows.get(row)?.get(column).copied()
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[char]> {
+        self.rows.iter().map(Vec::as_slice)
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Source::File(path) => write!(f, "{}", path.display()),
+            Source::Stdin => write!(f, "standard input"),
+            Source::Inline(_) => write!(f, "inline input"),
+        }
+    }
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputError::Usage(usage) => write!(f, "{}", usage),
+            InputError::Io { source, error } => write!(f, "unable to read {}, {}", source, error),
+            InputError::Parse {
+                source,
+                line,
+                value,
+                message,
+            } => write!(
+                f,
+                "{}, line {}: unable to parse '{}', {}",
+                source, line, value, message
+            ),
+            InputError::Ragged {
+                source,
+                line,
+                width,
+                expected,
+            } => write!(
+                f,
+                "{}, line {}: row has {} characters, expected {}",
+                source, line, width, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InputError::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::{Args, InputError, Source};
+    use std::path::PathBuf;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn arguments() {
+        let parsed = Args::parse(args(&["input.dat"]), "usage", 0..=0).unwrap();
+        assert_eq!(parsed.input, Source::File(PathBuf::from("input.dat")));
+
+        let parsed = Args::parse(args(&["-", "legend.dat"]), "usage", 0..=1).unwrap();
+        assert_eq!(parsed.input, Source::Stdin);
+        assert_eq!(parsed.rest, vec![String::from("legend.dat")]);
+
+        let parsed = Args::parse(args(&["--inline", "1\n2"]), "usage", ..).unwrap();
+        assert_eq!(parsed.input, Source::Inline(String::from("1\n2")));
+
+        for invalid in [&[][..], &["--inline"], &["input.dat", "extra"]] {
+            let error = Args::parse(args(invalid), "usage", 0..=0).unwrap_err();
+            assert!(matches!(error, InputError::Usage(_)), "{:?}", invalid);
+            assert!(error
+                .to_string()
+                .starts_with("usage\n[input] is a file name"));
+        }
+    }
+
+    #[test]
+    fn integers() {
+        let source = Source::Inline(String::from("1721\n979\n\n 366 \r\n"));
+        assert_eq!(source.integers::<usize>().unwrap(), vec![1721, 979, 366]);
+
+        let source = Source::Inline(String::from("1721\n97x9\n"));
+        assert_eq!(
+            source.integers::<usize>().unwrap_err().to_string(),
+            "inline input, line 2: unable to parse '97x9', invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn records() {
+        let source = Source::Inline(String::from("a b\nc\n\n  \n\nd\n"));
+        assert_eq!(
+            source.records().unwrap(),
+            vec![
+                vec![String::from("a b"), String::from("c")],
+                vec![String::from("d")]
+            ]
+        );
+    }
+
+    #[test]
+    fn grid() {
+        let grid = Source::Inline(String::from("..#\n#..\n\n")).grid().unwrap();
+        assert_eq!((grid.height(), grid.width()), (2, 3));
+        assert_eq!(grid.get(0, 2), Some('#'));
+        assert_eq!(grid.get(2, 0), None);
+
+        let error = Source::Inline(String::from("..#\n#.\n"))
+            .grid()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "inline input, line 2: row has 2 characters, expected 3"
+        );
+    }
+
+    #[test]
+    fn missing_file() {
+        let source = Source::from_argument("does-not-exist.dat");
+        let error = source.lines().unwrap_err();
+
+        assert!(matches!(error, InputError::Io { .. }));
+        assert!(error
+            .to_string()
+            .starts_with("unable to read does-not-exist.dat, "));
+    }
+}
@@ -0,0 +1,7 @@
+mod error;
+mod input;
+mod timing;
+
+pub use error::{run, Error};
+pub use input::{Args, Grid, InputError, Source};
+pub use timing::{parse_phase_line, phase, PHASE_PREFIX, TIMINGS_VAR};
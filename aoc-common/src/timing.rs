@@ -0,0 +1,53 @@
+use std::env;
+use std::sync::OnceLock;
+use std::time::Instant;
+
+/// Environment variable which turns on reporting of phase timings.
+pub const TIMINGS_VAR: &str = "AOC_TIMINGS";
+/// Prefix of every line with a phase timing, written to stderr as
+/// `aoc-phase <name> <nanoseconds>`.
+pub const PHASE_PREFIX: &str = "aoc-phase";
+
+/// Runs a single phase of the solver, e.g. parsing of the input. If
+/// `AOC_TIMINGS` is set, the duration of the phase is written to stderr, so
+/// the runner can measure every phase separately.
+pub fn phase<T, F>(name: &str, run: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    if !*ENABLED.get_or_init(|| env::var_os(TIMINGS_VAR).is_some()) {
+        return run();
+    }
+
+    let start = Instant::now();
+    let result = run();
+    eprintln!("{} {} {}", PHASE_PREFIX, name, start.elapsed().as_nanos());
+    result
+}
+
+/// Reads a line written by `phase`. Returns the name of the phase and its
+/// duration in nanoseconds.
+pub fn parse_phase_line(line: &str) -> Option<(&str, u64)> {
+    let mut parts = line.strip_prefix(PHASE_PREFIX)?.split_ascii_whitespace();
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(name), Some(nanos), None) => Some((name, nanos.parse().ok()?)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::parse_phase_line;
+
+    #[test]
+    fn phase_lines() {
+        assert_eq!(
+            parse_phase_line("aoc-phase parse 1200"),
+            Some(("parse", 1200))
+        );
+        assert_eq!(parse_phase_line("aoc-phase parse"), None);
+        assert_eq!(parse_phase_line("aoc-phase parse 1.5"), None);
+        assert_eq!(parse_phase_line("unable to read input.dat"), None);
+    }
+}
@@ -0,0 +1,173 @@
+use crate::registry::Solver;
+use aoc_common::Error;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Name of the file with known answers, in the root of the repository.
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// Correct answers of the solvers, for every input they were verified on.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Answers(BTreeMap<(u8, u8, String), String>);
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AnswersFile {
+    #[serde(rename = "answer", default)]
+    answers: Vec<KnownAnswer>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KnownAnswer {
+    day: u8,
+    part: u8,
+    /// Hash of the input, see `input_hash()`.
+    input: String,
+    answer: String,
+}
+
+impl Answers {
+    /// Loads answers from the file. Missing file has no answers.
+    pub fn load(file_name: &Path) -> Result<Answers, Error> {
+        if !file_name.exists() {
+            return Ok(Answers::default());
+        }
+        let raw_answers = std::fs::read_to_string(file_name)
+            .map_err(|e| Error::io(format!("unable to read {}", file_name.display()), e))?;
+        Answers::parse(&raw_answers)
+    }
+
+    fn parse(raw_answers: &str) -> Result<Answers, Error> {
+        let answers_file = toml::from_str::<AnswersFile>(raw_answers)
+            .map_err(|e| Error::Parse(format!("unable to parse {}, {}", ANSWERS_FILE, e)))?;
+        let mut answers = Answers::default();
+        for known in answers_file.answers {
+            let key = (known.day, known.part, known.input);
+            if answers.0.insert(key, known.answer).is_some() {
+                return Err(Error::Validation(format!(
+                    "{} has two answers of day {} part {} for the same input",
+                    ANSWERS_FILE, known.day, known.part
+                )));
+            }
+        }
+        Ok(answers)
+    }
+
+    pub fn save(&self, file_name: &Path) -> Result<(), Error> {
+        std::fs::write(file_name, self.to_toml())
+            .map_err(|e| Error::io(format!("unable to write {}", file_name.display()), e))
+    }
+
+    fn to_toml(&self) -> String {
+        let answers = self
+            .0
+            .iter()
+            .map(|((day, part, input), answer)| KnownAnswer {
+                day: *day,
+                part: *part,
+                input: input.clone(),
+                answer: answer.clone(),
+            })
+            .collect();
+        // This `unwrap()` is safe because answers contain only numbers and
+        // strings.
+        toml::to_string(&AnswersFile { answers }).unwrap()
+    }
+
+    pub fn get(&self, solver: &Solver, input_hash: &str) -> Option<&str> {
+        self.0
+            .get(&(solver.day, solver.part, input_hash.to_string()))
+            .map(String::as_str)
+    }
+
+    pub fn insert(&mut self, solver: &Solver, input_hash: &str, answer: &str) {
+        self.0.insert(
+            (solver.day, solver.part, input_hash.to_string()),
+            answer.to_string(),
+        );
+    }
+}
+
+/// 64-bit FNV-1a hash of the input, as 16 hexadecimal digits. It only tells
+/// inputs apart, it isn't meant to be secure.
+pub fn input_hash(input: &[u8]) -> String {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    let hash = input.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    });
+    format!("{:016x}", hash)
+}
+
+/// Answer printed by a solver: the last number in the last line of its
+/// output, e.g. `342` in `Correct passwords: 342`.
+pub fn extract_answer(stdout: &str) -> Option<&str> {
+    let line = stdout.lines().rev().find(|line| !line.trim().is_empty())?;
+    let end = line.rfind(|c: char| c.is_ascii_digit())? + 1;
+    let start = line[..end]
+        .rfind(|c: char| !c.is_ascii_digit())
+        .map_or(0, |i| i + 1);
+    // Negative numbers keep their sign.
+    let start = match line[..start].strip_suffix('-') {
+        Some(prefix) => prefix.len(),
+        None => start,
+    };
+    Some(&line[start..end])
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::{extract_answer, input_hash, Answers};
+    use crate::registry::Solver;
+
+    #[test]
+    fn answers() {
+        let solver = Solver {
+            day: 2,
+            part: 1,
+            path: String::from("day-02/password-philosophy"),
+            args: Vec::new(),
+        };
+        let mut answers = Answers::default();
+        answers.insert(&solver, "00ff", "2");
+
+        let saved = answers.to_toml();
+        assert_eq!(
+            saved,
+            "[[answer]]\nday = 2\npart = 1\ninput = \"00ff\"\nanswer = \"2\"\n"
+        );
+        let loaded = Answers::parse(&saved).unwrap();
+        assert_eq!(loaded.get(&solver, "00ff"), Some("2"));
+        assert_eq!(loaded.get(&solver, "ff00"), None);
+
+        let duplicate = format!("{}{}", saved, saved);
+        assert_eq!(Answers::parse(&duplicate).unwrap_err().exit_code(), 5);
+    }
+
+    #[test]
+    fn hashes() {
+        assert_eq!(input_hash(b""), "cbf29ce484222325");
+        assert_eq!(input_hash(b"a"), "af63dc4c8601ec8c");
+        assert_ne!(input_hash(b"1721\n979\n"), input_hash(b"1721\n978\n"));
+    }
+
+    #[test]
+    fn answers_in_output() {
+        assert_eq!(extract_answer("[514579]\n"), Some("514579"));
+        assert_eq!(extract_answer("Correct passwords: 2\n\n"), Some("2"));
+        assert_eq!(
+            extract_answer(
+                "Slope 1: tree = 2, open = 9, weighted cost = 2\n\
+                 Encountered trees on slopes: [2, 7], product all of them = 14\n"
+            ),
+            Some("14")
+        );
+        assert_eq!(extract_answer("Sum: -12 of 3\n"), Some("3"));
+        assert_eq!(extract_answer("Sum: -12\n"), Some("-12"));
+        assert_eq!(extract_answer("No answer\n"), None);
+        assert_eq!(extract_answer(""), None);
+    }
+}
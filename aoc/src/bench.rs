@@ -0,0 +1,347 @@
+use crate::inputs::Inputs;
+use crate::registry::{Registry, Solver};
+use crate::runner::{self, Run};
+use aoc_common::Error;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+
+/// Name of the file with the saved baseline, in the root of the repository.
+pub const BASELINE_FILE: &str = "bench-baseline.toml";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub day: Option<u8>,
+    pub iterations: usize,
+    /// Allowed slowdown of the median against the baseline, in percent.
+    pub threshold: f64,
+    pub save_baseline: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            day: None,
+            iterations: 20,
+            threshold: 10.0,
+            save_baseline: false,
+        }
+    }
+}
+
+/// Summary of all timings of a single phase.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub p99: Duration,
+}
+
+/// Median of every phase of every solver, saved by an earlier benchmark.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Baseline(BTreeMap<(u8, u8, String), Duration>);
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BaselineFile {
+    #[serde(rename = "timing", default)]
+    timings: Vec<BaselineTiming>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BaselineTiming {
+    day: u8,
+    part: u8,
+    phase: String,
+    median_ns: u64,
+}
+
+impl Stats {
+    /// Returns `None` if there are no samples.
+    pub fn new(samples: &[Duration]) -> Option<Stats> {
+        let mut samples = samples.to_vec();
+        samples.sort_unstable();
+        Some(Stats {
+            min: *samples.first()?,
+            median: percentile(&samples, 50.0),
+            p99: percentile(&samples, 99.0),
+        })
+    }
+}
+
+/// Nearest-rank percentile of sorted, non-empty samples.
+fn percentile(samples: &[Duration], percent: f64) -> Duration {
+    let rank = (percent / 100.0 * samples.len() as f64).ceil() as usize;
+    samples[rank.clamp(1, samples.len()) - 1]
+}
+
+impl Baseline {
+    /// Loads the baseline from the file. Missing file is an empty baseline.
+    pub fn load(file_name: &Path) -> Result<Baseline, Error> {
+        if !file_name.exists() {
+            return Ok(Baseline::default());
+        }
+        let raw_baseline = std::fs::read_to_string(file_name)
+            .map_err(|e| Error::io(format!("unable to read {}", file_name.display()), e))?;
+        Baseline::parse(&raw_baseline)
+    }
+
+    fn parse(raw_baseline: &str) -> Result<Baseline, Error> {
+        let baseline_file = toml::from_str::<BaselineFile>(raw_baseline)
+            .map_err(|e| Error::Parse(format!("unable to parse {}, {}", BASELINE_FILE, e)))?;
+        Ok(Baseline(
+            baseline_file
+                .timings
+                .into_iter()
+                .map(|timing| {
+                    let key = (timing.day, timing.part, timing.phase);
+                    (key, Duration::from_nanos(timing.median_ns))
+                })
+                .collect(),
+        ))
+    }
+
+    pub fn save(&self, file_name: &Path) -> Result<(), Error> {
+        std::fs::write(file_name, self.to_toml())
+            .map_err(|e| Error::io(format!("unable to write {}", file_name.display()), e))
+    }
+
+    fn to_toml(&self) -> String {
+        let timings = self
+            .0
+            .iter()
+            .map(|((day, part, phase), median)| BaselineTiming {
+                day: *day,
+                part: *part,
+                phase: phase.clone(),
+                median_ns: median.as_nanos() as u64,
+            })
+            .collect();
+        // This `unwrap()` is safe because the baseline contains only numbers
+        // and strings.
+        toml::to_string(&BaselineFile { timings }).unwrap()
+    }
+
+    pub fn get(&self, solver: &Solver, phase: &str) -> Option<Duration> {
+        self.0
+            .get(&(solver.day, solver.part, phase.to_string()))
+            .copied()
+    }
+
+    pub fn insert(&mut self, solver: &Solver, phase: &str, median: Duration) {
+        self.0
+            .insert((solver.day, solver.part, phase.to_string()), median);
+    }
+}
+
+/// Change of the median against the baseline, in percent.
+fn change(median: Duration, baseline: Duration) -> f64 {
+    if baseline.is_zero() {
+        return 0.0;
+    }
+    (median.as_secs_f64() / baseline.as_secs_f64() - 1.0) * 100.0
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    match nanos {
+        0..=999 => format!("{} ns", nanos),
+        1_000..=999_999 => format!("{:.1} us", nanos as f64 / 1e3),
+        1_000_000..=999_999_999 => format!("{:.1} ms", nanos as f64 / 1e6),
+        _ => format!("{:.2} s", nanos as f64 / 1e9),
+    }
+}
+
+/// Builds every selected solver, runs it `iterations` times on its input and
+/// prints timings of every phase. Phases slower than the baseline are
+/// reported as an error.
+pub fn bench(registry: &Registry, inputs: &Inputs, options: &Options) -> Result<(), Error> {
+    if options.iterations == 0 {
+        return Err(Error::Usage(String::from(
+            "number of iterations must be greater than 0",
+        )));
+    }
+    let baseline_file = registry.root.join(BASELINE_FILE);
+    let mut baseline = Baseline::load(&baseline_file)?;
+    let mut failures = Vec::new();
+    let mut regressions = 0;
+
+    println!(
+        "{:<14} {:<8} {:>10} {:>10} {:>10} {:>10}",
+        "solver", "phase", "min", "median", "p99", "baseline"
+    );
+    for solver in registry.select(options.day)? {
+        let timings = match measure(registry, inputs, solver, options.iterations) {
+            Ok(timings) => timings,
+            Err(e) => {
+                eprintln!("{}", e);
+                failures.push(solver.name());
+                continue;
+            }
+        };
+
+        for (phase, samples) in timings {
+            // This `unwrap()` is safe because every phase has at least one
+            // sample.
+            let stats = Stats::new(&samples).unwrap();
+            let comparison = match baseline.get(solver, &phase) {
+                Some(previous) => {
+                    let change = change(stats.median, previous);
+                    let regression = change > options.threshold;
+                    if regression {
+                        regressions += 1;
+                    }
+                    format!(
+                        "{:>10} {:+.1}%{}",
+                        format_duration(previous),
+                        change,
+                        if regression { " REGRESSION" } else { "" }
+                    )
+                }
+                None => format!("{:>10}", "-"),
+            };
+            println!(
+                "{:<14} {:<8} {:>10} {:>10} {:>10} {}",
+                solver.name(),
+                phase,
+                format_duration(stats.min),
+                format_duration(stats.median),
+                format_duration(stats.p99),
+                comparison
+            );
+            if options.save_baseline {
+                baseline.insert(solver, &phase, stats.median);
+            }
+        }
+    }
+
+    if options.save_baseline {
+        baseline.save(&baseline_file)?;
+        println!("Baseline saved to {}", baseline_file.display());
+    }
+    if !failures.is_empty() {
+        return Err(Error::Check(format!(
+            "unable to benchmark: {}",
+            failures.join(", ")
+        )));
+    }
+    if regressions > 0 && !options.save_baseline {
+        return Err(Error::Check(format!(
+            "{} phases are more than {}% slower than the baseline",
+            regressions, options.threshold
+        )));
+    }
+    Ok(())
+}
+
+/// Samples of every phase of the solver, in the order of the first run.
+fn measure(
+    registry: &Registry,
+    inputs: &Inputs,
+    solver: &Solver,
+    iterations: usize,
+) -> Result<Vec<(String, Vec<Duration>)>, Error> {
+    let executable = runner::build(&registry.root, solver)?;
+    let input = inputs.input_of(&registry.root, solver);
+    // The first run only warms up caches of the system.
+    check_run(solver, runner::run(&executable, solver, &input, true)?)?;
+
+    let mut timings: Vec<(String, Vec<Duration>)> = Vec::new();
+    for _ in 0..iterations {
+        let run = check_run(solver, runner::run(&executable, solver, &input, true)?)?;
+        for (phase, duration) in run.all_phases() {
+            match timings.iter_mut().find(|(name, _)| *name == phase) {
+                Some((_, samples)) => samples.push(duration),
+                None => timings.push((phase, vec![duration])),
+            }
+        }
+    }
+    Ok(timings)
+}
+
+fn check_run(solver: &Solver, run: Run) -> Result<Run, Error> {
+    if run.success() {
+        return Ok(run);
+    }
+    Err(Error::Check(format!(
+        "{}: solver failed with exit code {:?}\n{}",
+        solver.name(),
+        run.exit_code,
+        run.stderr.trim_end()
+    )))
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::{change, format_duration, Baseline, Stats};
+    use crate::registry::Solver;
+    use std::time::Duration;
+
+    fn micros(values: &[u64]) -> Vec<Duration> {
+        values
+            .iter()
+            .map(|value| Duration::from_micros(*value))
+            .collect()
+    }
+
+    #[test]
+    fn stats() {
+        let samples = micros(&[5, 1, 4, 2, 3]);
+        assert_eq!(
+            Stats::new(&samples),
+            Some(Stats {
+                min: Duration::from_micros(1),
+                median: Duration::from_micros(3),
+                p99: Duration::from_micros(5),
+            })
+        );
+
+        let mut samples = micros(&[10; 99]);
+        samples.push(Duration::from_micros(500));
+        let stats = Stats::new(&samples).unwrap();
+        assert_eq!(stats.p99, Duration::from_micros(10));
+
+        assert_eq!(Stats::new(&[]), None);
+    }
+
+    #[test]
+    fn baseline() {
+        let solver = Solver {
+            day: 3,
+            part: 2,
+            path: String::from("day-03/part-two"),
+            args: Vec::new(),
+        };
+        let mut baseline = Baseline::default();
+        baseline.insert(&solver, "parse", Duration::from_nanos(1500));
+
+        let saved = baseline.to_toml();
+        assert_eq!(
+            saved,
+            "[[timing]]\nday = 3\npart = 2\nphase = \"parse\"\nmedian_ns = 1500\n"
+        );
+        let loaded = Baseline::parse(&saved).unwrap();
+        assert_eq!(
+            loaded.get(&solver, "parse"),
+            Some(Duration::from_nanos(1500))
+        );
+        assert_eq!(loaded.get(&solver, "solve"), None);
+    }
+
+    #[test]
+    fn regression() {
+        let baseline = Duration::from_millis(10);
+        assert!((change(Duration::from_millis(12), baseline) - 20.0).abs() < 1e-9);
+        assert!(change(Duration::from_millis(9), baseline) < 0.0);
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(format_duration(Duration::from_nanos(999)), "999 ns");
+        assert_eq!(format_duration(Duration::from_nanos(1_250)), "1.2 us");
+        assert_eq!(format_duration(Duration::from_micros(2_500)), "2.5 ms");
+        assert_eq!(format_duration(Duration::from_millis(1_500)), "1.50 s");
+    }
+}
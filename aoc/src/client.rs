@@ -0,0 +1,208 @@
+use crate::answers::input_hash;
+use aoc_common::Error;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Session cookie of adventofcode.com, needed to download inputs and submit
+/// answers.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+/// URL of the puzzles of the year, without a trailing slash.
+pub const ENDPOINT_VAR: &str = "AOC_ENDPOINT";
+/// Directory with cached inputs and the history of submissions.
+pub const CACHE_VAR: &str = "AOC_CACHE";
+/// Minimal time between two requests to the server, in seconds.
+pub const INTERVAL_VAR: &str = "AOC_MIN_INTERVAL";
+
+const DEFAULT_ENDPOINT: &str = "https://adventofcode.com/2020";
+/// Default cache directory, relative to the root of the repository.
+const DEFAULT_CACHE_DIR: &str = ".aoc-cache";
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(5);
+/// File in the cache directory with the time of the last request.
+const LAST_REQUEST_FILE: &str = "last-request";
+const USER_AGENT: &str = "aoc-2020-runner (rust, ureq)";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub endpoint: String,
+    pub session: Option<String>,
+    pub cache_dir: PathBuf,
+    pub min_interval: Duration,
+}
+
+/// Client of the Advent of Code server. It sends at most one request per
+/// the minimal interval, also together with other processes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Client {
+    pub config: Config,
+}
+
+impl Config {
+    /// Configuration from the environment variables, with defaults for the
+    /// repository in `root`.
+    pub fn from_env(root: &Path) -> Result<Config, Error> {
+        let min_interval = match env::var(INTERVAL_VAR) {
+            Ok(seconds) => seconds
+                .parse::<f64>()
+                .ok()
+                .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+                .ok_or_else(|| {
+                    Error::Usage(format!(
+                        "{} must be a number of seconds: {}",
+                        INTERVAL_VAR, seconds
+                    ))
+                })?,
+            Err(_) => DEFAULT_INTERVAL,
+        };
+        Ok(Config {
+            endpoint: env::var(ENDPOINT_VAR)
+                .map(|endpoint| endpoint.trim_end_matches('/').to_string())
+                .unwrap_or_else(|_| String::from(DEFAULT_ENDPOINT)),
+            session: env::var(SESSION_VAR)
+                .ok()
+                .filter(|session| !session.is_empty()),
+            cache_dir: env::var_os(CACHE_VAR)
+                .map(PathBuf::from)
+                .unwrap_or_else(|| root.join(DEFAULT_CACHE_DIR)),
+            min_interval,
+        })
+    }
+}
+
+impl Client {
+    pub fn new(config: Config) -> Client {
+        Client { config }
+    }
+
+    /// Directory in the cache with files of the current session.
+    pub fn session_dir(&self) -> PathBuf {
+        // The session itself is a secret, so only its hash is a part of the
+        // path.
+        let session = match &self.config.session {
+            Some(session) => input_hash(session.as_bytes()),
+            None => String::from("no-session"),
+        };
+        self.config.cache_dir.join(session)
+    }
+
+    /// Downloads the page, e.g. `/day/1/input`. `offline_hint` tells what to
+    /// do without the server.
+    pub fn get(&self, path: &str, offline_hint: &str) -> Result<String, Error> {
+        let url = format!("{}{}", self.config.endpoint, path);
+        let session = self.session(offline_hint)?;
+        self.wait_for_turn()?;
+        let response = agent()
+            .get(&url)
+            .set("Cookie", &format!("session={}", session))
+            .call();
+        read_response(
+            &format!("unable to download {}", url),
+            response,
+            offline_hint,
+        )
+    }
+
+    /// Posts the form to the page, e.g. `/day/1/answer`.
+    pub fn post(
+        &self,
+        path: &str,
+        form: &[(&str, &str)],
+        offline_hint: &str,
+    ) -> Result<String, Error> {
+        let url = format!("{}{}", self.config.endpoint, path);
+        let session = self.session(offline_hint)?;
+        self.wait_for_turn()?;
+        let response = agent()
+            .post(&url)
+            .set("Cookie", &format!("session={}", session))
+            .send_form(form);
+        read_response(
+            &format!("unable to post to {}", url),
+            response,
+            offline_hint,
+        )
+    }
+
+    fn session(&self, offline_hint: &str) -> Result<&str, Error> {
+        self.config.session.as_deref().ok_or_else(|| {
+            Error::Usage(format!(
+                "set {} to the session cookie of adventofcode.com, or {}",
+                SESSION_VAR, offline_hint
+            ))
+        })
+    }
+
+    /// Waits until the minimal interval since the last request to the
+    /// server passes, also the request of another process.
+    fn wait_for_turn(&self) -> Result<(), Error> {
+        let file_name = self.config.cache_dir.join(LAST_REQUEST_FILE);
+        let last_request = fs::read_to_string(&file_name)
+            .ok()
+            .and_then(|millis| millis.trim().parse::<u64>().ok())
+            .map(Duration::from_millis);
+        if let Some(last_request) = last_request {
+            let next_request = last_request + self.config.min_interval;
+            if let Some(wait) = next_request.checked_sub(since_epoch()) {
+                eprintln!(
+                    "Waiting {:.1} s before the next request to the server",
+                    wait.as_secs_f64()
+                );
+                thread::sleep(wait);
+            }
+        }
+
+        fs::create_dir_all(&self.config.cache_dir)
+            .and_then(|_| fs::write(&file_name, since_epoch().as_millis().to_string()))
+            .map_err(|e| Error::io(format!("unable to write {}", file_name.display()), e))
+    }
+}
+
+/// Time since the Unix epoch, used as the time of events saved in files.
+pub fn since_epoch() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .timeout_connect(Duration::from_secs(10))
+        .timeout(Duration::from_secs(30))
+        .user_agent(USER_AGENT)
+        .build()
+}
+
+fn read_response(
+    context: &str,
+    response: Result<ureq::Response, ureq::Error>,
+    offline_hint: &str,
+) -> Result<String, Error> {
+    let message = match response {
+        Ok(response) => return response.into_string().map_err(|e| Error::io(context, e)),
+        Err(ureq::Error::Status(404, _)) => {
+            String::from("server answered with status 404, the puzzle may not be unlocked yet")
+        }
+        Err(ureq::Error::Status(code @ (400 | 401 | 403 | 500), _)) => format!(
+            "server rejected the request with status {}, {} may be invalid or expired",
+            code, SESSION_VAR
+        ),
+        Err(ureq::Error::Status(code, _)) => format!("server answered with status {}", code),
+        Err(ureq::Error::Transport(e)) => {
+            // The message of the error repeats the URL, the kind and the
+            // cause are enough.
+            let cause = std::error::Error::source(&e)
+                .map(|cause| format!(": {}", cause))
+                .or_else(|| e.message().map(|message| format!(": {}", message)));
+            format!(
+                "{}{}; if you are offline, {}",
+                e.kind(),
+                cause.unwrap_or_default(),
+                offline_hint
+            )
+        }
+    };
+    Err(Error::io(context, io::Error::other(message)))
+}
@@ -0,0 +1,285 @@
+use crate::registry::{Registry, Solver};
+use crate::runner;
+use aoc_common::Error;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory with examples of all days, in the root of the repository.
+pub const EXAMPLES_DIR: &str = "examples";
+
+/// Single example: the input, e.g. `examples/day01/expense-report.txt`, and
+/// the expected output of one part, e.g. `expense-report.part1.out`.
+///
+/// The expected output is stdout of the solver. Output of a failed solver is
+/// `exit code N` followed by its stderr, so also errors can be expected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Example {
+    pub day: u8,
+    pub part: u8,
+    pub name: String,
+    pub input: PathBuf,
+    pub expected: PathBuf,
+}
+
+impl Example {
+    /// Short name used in reports, e.g. `day01/expense-report part 1`.
+    pub fn name(&self) -> String {
+        format!("day{:02}/{} part {}", self.day, self.name, self.part)
+    }
+}
+
+/// Finds examples of the given day, or of all days, sorted by day, name and
+/// part. An expected output without an input is an error.
+pub fn discover(root: &Path, day: Option<u8>) -> Result<Vec<Example>, Error> {
+    let examples_dir = root.join(EXAMPLES_DIR);
+    let mut examples = Vec::new();
+    for day_dir in read_dir(&examples_dir)? {
+        let Some(dir_day) = day_dir
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("day"))
+            .and_then(|day| day.parse::<u8>().ok())
+        else {
+            continue;
+        };
+        if day.is_some_and(|day| day != dir_day) {
+            continue;
+        }
+
+        for file_name in read_dir(&day_dir)? {
+            let Some(name) = file_name.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let Some((stem, part)) = name
+                .strip_suffix(".out")
+                .and_then(|name| name.rsplit_once(".part"))
+            else {
+                continue;
+            };
+            let part = part
+                .parse::<u8>()
+                .ok()
+                .filter(|part| (1..=2).contains(part))
+                .ok_or_else(|| {
+                    Error::Validation(format!("{}: part must be 1 or 2", file_name.display()))
+                })?;
+            let input = day_dir.join(format!("{}.txt", stem));
+            if !input.is_file() {
+                return Err(Error::Validation(format!(
+                    "{}: input {} doesn't exist",
+                    file_name.display(),
+                    input.display()
+                )));
+            }
+            examples.push(Example {
+                day: dir_day,
+                part,
+                name: stem.to_string(),
+                input,
+                expected: file_name,
+            });
+        }
+    }
+    examples.sort_by(|a, b| (a.day, &a.name, a.part).cmp(&(b.day, &b.name, b.part)));
+    Ok(examples)
+}
+
+/// Entries of the directory, sorted by name. Missing directory is empty.
+fn read_dir(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut entries = fs::read_dir(dir)
+        .and_then(|entries| {
+            entries
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<Vec<PathBuf>, _>>()
+        })
+        .map_err(|e| Error::io(format!("unable to read {}", dir.display()), e))?;
+    entries.sort();
+    Ok(entries)
+}
+
+/// Runs the solver of every example and compares its output with the
+/// expected one. Mismatches are printed with a diff and reported as an
+/// error.
+pub fn run_examples(registry: &Registry, day: Option<u8>) -> Result<(), Error> {
+    let examples = discover(&registry.root, day)?;
+    if examples.is_empty() {
+        println!(
+            "No examples in {}",
+            registry.root.join(EXAMPLES_DIR).display()
+        );
+        return Ok(());
+    }
+
+    let mut executables: HashMap<(u8, u8), Result<PathBuf, String>> = HashMap::new();
+    let mut failed = 0;
+    for example in examples.iter() {
+        let result = registry
+            .solvers
+            .iter()
+            .find(|solver| (solver.day, solver.part) == (example.day, example.part))
+            .ok_or_else(|| {
+                format!(
+                    "day {} part {} is not registered",
+                    example.day, example.part
+                )
+            })
+            .and_then(|solver| {
+                let executable = executables
+                    .entry((solver.day, solver.part))
+                    .or_insert_with(|| {
+                        runner::build(&registry.root, solver).map_err(|e| e.to_string())
+                    })
+                    .clone()?;
+                check(&executable, solver, example).map_err(|e| e.to_string())
+            });
+        match result {
+            Ok(None) => println!("PASS  {}", example.name()),
+            Ok(Some(diff)) => {
+                failed += 1;
+                println!("FAIL  {}\n{}", example.name(), diff);
+            }
+            Err(message) => {
+                failed += 1;
+                println!("ERROR {}\n{}", example.name(), message);
+            }
+        }
+    }
+
+    println!("Passed: {}, failed: {}", examples.len() - failed, failed);
+    if failed > 0 {
+        return Err(Error::Check(format!("{} examples failed", failed)));
+    }
+    Ok(())
+}
+
+/// Runs the solver on the example. Returns the diff of the outputs if they
+/// differ.
+pub fn check(
+    executable: &Path,
+    solver: &Solver,
+    example: &Example,
+) -> Result<Option<String>, Error> {
+    let expected = fs::read_to_string(&example.expected)
+        .map_err(|e| Error::io(format!("unable to read {}", example.expected.display()), e))?;
+    let run = runner::run(executable, solver, &example.input, false)?;
+    let actual = if run.success() {
+        run.stdout
+    } else {
+        let exit_code = run
+            .exit_code
+            .map_or_else(|| String::from("none"), |code| code.to_string());
+        format!("exit code {}\n{}", exit_code, run.stderr)
+    };
+
+    if expected.trim_end() == actual.trim_end() {
+        Ok(None)
+    } else {
+        Ok(Some(diff(&expected, &actual)))
+    }
+}
+
+/// Line diff of the outputs: common lines start with two spaces, missing
+/// lines with `- ` and extra lines with `+ `.
+pub fn diff(expected: &str, actual: &str) -> String {
+    let expected = expected.trim_end().lines().collect::<Vec<&str>>();
+    let actual = actual.trim_end().lines().collect::<Vec<&str>>();
+
+    // Length of the longest common subsequence of the suffixes.
+    let mut common = vec![vec![0; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            common[i][j] = if expected[i] == actual[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            diff.push_str(&format!("  {}\n", expected[i]));
+            i += 1;
+            j += 1;
+        } else if j == actual.len() || (i < expected.len() && common[i + 1][j] >= common[i][j + 1])
+        {
+            diff.push_str(&format!("- {}\n", expected[i]));
+            i += 1;
+        } else {
+            diff.push_str(&format!("+ {}\n", actual[j]));
+            j += 1;
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::{diff, discover};
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn diffs() {
+        assert_eq!(
+            diff(
+                "Slope 1: 2\nSlope 2: 7\nProduct: 14\n",
+                "Slope 1: 2\nSlope 2: 8\nProduct: 16"
+            ),
+            "  Slope 1: 2\n- Slope 2: 7\n- Product: 14\n+ Slope 2: 8\n+ Product: 16\n"
+        );
+        assert_eq!(diff("a\nb\n", "a\nx\nb\n"), "  a\n+ x\n  b\n");
+        assert_eq!(diff("", "a"), "+ a\n");
+    }
+
+    #[test]
+    fn repository_examples() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+        let examples = discover(root, None).unwrap();
+
+        for day in 1..=4 {
+            for part in 1..=2 {
+                assert!(
+                    examples
+                        .iter()
+                        .any(|example| (example.day, example.part) == (day, part)),
+                    "day {} part {}",
+                    day,
+                    part
+                );
+            }
+        }
+        assert!(discover(root, Some(2))
+            .unwrap()
+            .iter()
+            .all(|example| example.day == 2));
+    }
+
+    #[test]
+    fn missing_input() {
+        let root = std::env::temp_dir().join(format!("aoc-examples-{}", std::process::id()));
+        let day_dir = root.join("examples/day07");
+        fs::create_dir_all(&day_dir).unwrap();
+        fs::write(day_dir.join("bags.txt"), "").unwrap();
+        fs::write(day_dir.join("bags.part1.out"), "4\n").unwrap();
+        fs::write(day_dir.join("README.md"), "").unwrap();
+
+        let examples = discover(&root, None).unwrap();
+        assert_eq!(examples.len(), 1);
+        assert_eq!(examples[0].name(), "day07/bags part 1");
+
+        fs::write(day_dir.join("typo.part2.out"), "4\n").unwrap();
+        assert_eq!(discover(&root, None).unwrap_err().exit_code(), 5);
+        fs::write(day_dir.join("bags.part3.out"), "4\n").unwrap();
+        fs::remove_file(day_dir.join("typo.part2.out")).unwrap();
+        assert_eq!(discover(&root, None).unwrap_err().exit_code(), 5);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,190 @@
+use crate::client::Client;
+use crate::registry::Solver;
+use aoc_common::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Puzzle inputs cached by day and session. Both parts of a day read the
+/// same cached file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inputs {
+    client: Client,
+}
+
+impl Inputs {
+    pub fn new(client: Client) -> Inputs {
+        Inputs { client }
+    }
+
+    /// Cached input of the day, which may not exist yet.
+    pub fn path(&self, day: u8) -> PathBuf {
+        self.client
+            .session_dir()
+            .join(format!("day-{:02}.txt", day))
+    }
+
+    pub fn cached(&self, day: u8) -> Option<PathBuf> {
+        Some(self.path(day)).filter(|path| path.is_file())
+    }
+
+    /// Input of the solver: the cached input of its day, or `input.dat` of
+    /// its day if there is none.
+    pub fn input_of(&self, root: &Path, solver: &Solver) -> PathBuf {
+        self.cached(solver.day)
+            .unwrap_or_else(|| solver.input(root))
+    }
+
+    /// Copies the file into the cache as the input of the day.
+    pub fn import(&self, day: u8, file_name: &Path) -> Result<PathBuf, Error> {
+        let input = fs::read_to_string(file_name)
+            .map_err(|e| Error::io(format!("unable to read {}", file_name.display()), e))?;
+        self.store(day, &input)
+    }
+
+    /// Cached input of the day, downloaded first if it isn't in the cache.
+    pub fn fetch(&self, day: u8) -> Result<PathBuf, Error> {
+        if let Some(path) = self.cached(day) {
+            return Ok(path);
+        }
+        let offline_hint = format!("import the input with `aoc input {} --import FILE`", day);
+        let input = self
+            .client
+            .get(&format!("/day/{}/input", day), &offline_hint)?;
+        if input.trim().is_empty() {
+            return Err(Error::io(
+                format!("unable to download input of day {}", day),
+                io::Error::other("server sent an empty input"),
+            ));
+        }
+        self.store(day, &input)
+    }
+
+    fn store(&self, day: u8, input: &str) -> Result<PathBuf, Error> {
+        let path = self.path(day);
+        // This `unwrap()` is safe because the path is inside the cache
+        // directory.
+        let dir = path.parent().unwrap();
+        fs::create_dir_all(dir)
+            .map_err(|e| Error::io(format!("unable to create {}", dir.display()), e))?;
+        // Interrupted writes must not leave a partial input in the cache.
+        let partial = path.with_extension("part");
+        fs::write(&partial, input)
+            .and_then(|_| fs::rename(&partial, &path))
+            .map_err(|e| Error::io(format!("unable to write {}", path.display()), e))?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::Inputs;
+    use crate::client::Client;
+    use crate::mock::{test_config, MockServer, Response};
+    use crate::registry::Solver;
+    use std::fs;
+    use std::net::TcpListener;
+    use std::path::PathBuf;
+    use std::time::{Duration, Instant};
+
+    fn cached_inputs(name: &str, endpoint: String, session: Option<&str>) -> (Inputs, PathBuf) {
+        let config = test_config(&format!("inputs-{}", name), endpoint, session);
+        let cache_dir = config.cache_dir.clone();
+        (Inputs::new(Client::new(config)), cache_dir)
+    }
+
+    #[test]
+    fn fetch() {
+        let server =
+            MockServer::start(
+                |request| match (request.path.as_str(), request.header("cookie")) {
+                    ("/day/3/input", Some("session=secret")) => Response::new(200, "..#\n#..\n"),
+                    (_, Some("session=secret")) => Response::new(404, "Not found"),
+                    _ => Response::new(400, "Puzzle inputs differ by user."),
+                },
+            );
+        let (inputs, cache_dir) = cached_inputs("fetch", server.url(), Some("secret"));
+
+        let path = inputs.fetch(3).unwrap();
+        assert!(path.starts_with(&cache_dir));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "..#\n#..\n");
+        // Second call reads the cache.
+        assert_eq!(inputs.fetch(3).unwrap(), path);
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(server.requests()[0].method, "GET");
+
+        let solver = |part| Solver {
+            day: 3,
+            part,
+            path: String::from("day-03/part-two"),
+            args: Vec::new(),
+        };
+        let root = PathBuf::from("/repo");
+        assert_eq!(inputs.input_of(&root, &solver(1)), path);
+        assert_eq!(inputs.input_of(&root, &solver(2)), path);
+
+        let error = inputs.fetch(4).unwrap_err();
+        assert_eq!(error.exit_code(), 3);
+        assert!(error
+            .to_string()
+            .ends_with("status 404, the puzzle may not be unlocked yet"));
+
+        let (other_session, _) = cached_inputs("fetch-other", server.url(), Some("other"));
+        assert!(other_session.cached(3).is_none());
+        let error = other_session.fetch(3).unwrap_err();
+        assert!(error.to_string().contains("may be invalid or expired"));
+
+        fs::remove_dir_all(&cache_dir).unwrap();
+    }
+
+    #[test]
+    fn offline() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+        let (inputs, cache_dir) = cached_inputs("offline", url, Some("secret"));
+
+        let error = inputs.fetch(1).unwrap_err();
+        assert_eq!(error.exit_code(), 3);
+        assert!(error
+            .to_string()
+            .ends_with("if you are offline, import the input with `aoc input 1 --import FILE`"));
+
+        let _ = fs::remove_dir_all(&cache_dir);
+
+        let (inputs, _) = cached_inputs("no-session", String::from("http://127.0.0.1:1"), None);
+        assert_eq!(inputs.fetch(1).unwrap_err().exit_code(), 2);
+    }
+
+    #[test]
+    fn import() {
+        let (inputs, cache_dir) = cached_inputs("import", String::new(), None);
+        let source = std::env::temp_dir().join(format!("aoc-import-{}.dat", std::process::id()));
+        fs::write(&source, "1721\n979\n").unwrap();
+
+        let path = inputs.import(1, &source).unwrap();
+        assert_eq!(inputs.cached(1), Some(path.clone()));
+        assert_eq!(inputs.fetch(1).unwrap(), path);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1721\n979\n");
+
+        fs::remove_file(&source).unwrap();
+        fs::remove_dir_all(&cache_dir).unwrap();
+    }
+
+    #[test]
+    fn rate_limit() {
+        let server = MockServer::start(|_| Response::new(200, "1\n"));
+        let (inputs, cache_dir) = cached_inputs("rate-limit", server.url(), Some("secret"));
+        let mut client = inputs.client;
+        client.config.min_interval = Duration::from_millis(300);
+        let inputs = Inputs::new(client);
+
+        let start = Instant::now();
+        inputs.fetch(1).unwrap();
+        inputs.fetch(2).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(300));
+        assert_eq!(server.requests().len(), 2);
+
+        fs::remove_dir_all(&cache_dir).unwrap();
+    }
+}
@@ -0,0 +1,288 @@
+use crate::inputs::Inputs;
+use crate::registry::{Registry, Solver};
+use crate::runner;
+use aoc_common::Error;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+/// Days with an inspector of their intermediate state.
+pub const DAYS: [u8; 3] = [2, 3, 4];
+
+/// Intermediate state of a day, from the report of its solvers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Inspection {
+    /// Day 2: every password policy and whether it passes the rules of both
+    /// parts.
+    Policies(Vec<PolicyRow>),
+    /// Day 3: the map and the fields visited on every slope.
+    Map {
+        lines: Vec<String>,
+        slopes: Vec<Slope>,
+    },
+    /// Day 4: every passport and the validity of its fields.
+    Passports(Vec<PassportRow>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolicyRow {
+    pub line: usize,
+    pub policy: String,
+    pub password: String,
+    /// Result of the rule of every part, `None` if the part isn't
+    /// registered.
+    pub valid: [Option<bool>; 2],
+}
+
+/// Single line of the report of a day 2 solver.
+#[derive(Debug, Deserialize)]
+struct PolicyReport {
+    line: usize,
+    policy: String,
+    password: String,
+    valid: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Slope {
+    pub right: isize,
+    pub down: usize,
+    pub trees: usize,
+    /// Line and column of every visited field, both numbered from 0.
+    pub path: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MapReport {
+    slopes: Vec<Slope>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PassportRow {
+    pub passport: usize,
+    pub first_line: usize,
+    pub last_line: usize,
+    pub valid: bool,
+    pub fields: Vec<FieldValue>,
+    pub errors: Vec<FieldProblem>,
+    pub token_errors: Vec<TokenProblem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FieldValue {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FieldProblem {
+    pub field: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TokenProblem {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl PassportRow {
+    /// Every field which is present or has a problem, sorted by key, with
+    /// its value and the problem if it isn't valid.
+    pub fn field_statuses(&self) -> Vec<(&str, Option<&str>, Option<&str>)> {
+        let mut keys = self
+            .fields
+            .iter()
+            .map(|field| field.key.as_str())
+            .chain(self.errors.iter().map(|error| error.field.as_str()))
+            .collect::<Vec<&str>>();
+        keys.sort_unstable();
+        keys.dedup();
+        keys.into_iter()
+            .map(|key| {
+                let value = self
+                    .fields
+                    .iter()
+                    .find(|field| field.key == key)
+                    .map(|field| field.value.as_str());
+                let problem = self
+                    .errors
+                    .iter()
+                    .find(|error| error.field == key)
+                    .map(|error| error.message.as_str());
+                (key, value, problem)
+            })
+            .collect()
+    }
+}
+
+/// Runs the solvers of the day on their input with `--report json` and
+/// collects their reports.
+pub fn inspect(registry: &Registry, inputs: &Inputs, day: u8) -> Result<Inspection, Error> {
+    if !DAYS.contains(&day) {
+        return Err(Error::Usage(format!(
+            "day {} doesn't have an inspector",
+            day
+        )));
+    }
+    let solvers = registry.select(Some(day))?;
+    // This `unwrap()` is safe because `select()` returns at least one solver
+    // of the given day.
+    let last = *solvers.last().unwrap();
+    let report_of = |part: u8| {
+        solvers
+            .iter()
+            .find(|solver| solver.part == part)
+            .map(|solver| report(registry, inputs, solver))
+            .transpose()
+    };
+    match day {
+        2 => policies(report_of(1)?.as_deref(), report_of(2)?.as_deref()),
+        3 => {
+            let input = inputs.input_of(&registry.root, last);
+            let raw_map = std::fs::read_to_string(&input)
+                .map_err(|e| Error::io(format!("unable to read {}", input.display()), e))?;
+            map(&raw_map, &report(registry, inputs, last)?)
+        }
+        _ => passports(&report(registry, inputs, last)?),
+    }
+}
+
+/// Output of the solver run with `--report json`.
+fn report(registry: &Registry, inputs: &Inputs, solver: &Solver) -> Result<String, Error> {
+    let executable = runner::build(&registry.root, solver)?;
+    let mut reporting_solver = solver.clone();
+    reporting_solver
+        .args
+        .extend([String::from("--report"), String::from("json")]);
+    let input = inputs.input_of(&registry.root, solver);
+    let run = runner::run(&executable, &reporting_solver, &input, false)?;
+    if !run.success() {
+        return Err(Error::Check(format!(
+            "{}: report failed with exit code {:?}\n{}",
+            solver.name(),
+            run.exit_code,
+            run.stderr.trim_end()
+        )));
+    }
+    Ok(run.stdout)
+}
+
+fn parse_report<T: DeserializeOwned>(raw_report: &str) -> Result<T, Error> {
+    serde_json::from_str::<T>(raw_report)
+        .map_err(|e| Error::Parse(format!("unable to parse report of the solver, {}", e)))
+}
+
+/// Policies from the reports of both parts, which must list the same lines.
+fn policies(part_one: Option<&str>, part_two: Option<&str>) -> Result<Inspection, Error> {
+    let mut rows: Vec<PolicyRow> = Vec::new();
+    for (i, raw_report) in [part_one, part_two].into_iter().enumerate() {
+        let Some(raw_report) = raw_report else {
+            continue;
+        };
+        for report in parse_report::<Vec<PolicyReport>>(raw_report)? {
+            match rows.iter_mut().find(|row| row.line == report.line) {
+                Some(row) => row.valid[i] = Some(report.valid),
+                None => {
+                    let mut valid = [None, None];
+                    valid[i] = Some(report.valid);
+                    rows.push(PolicyRow {
+                        line: report.line,
+                        policy: report.policy,
+                        password: report.password,
+                        valid,
+                    });
+                }
+            }
+        }
+    }
+    rows.sort_by_key(|row| row.line);
+    Ok(Inspection::Policies(rows))
+}
+
+fn map(raw_map: &str, raw_report: &str) -> Result<Inspection, Error> {
+    let report = parse_report::<MapReport>(raw_report)?;
+    Ok(Inspection::Map {
+        lines: raw_map.lines().map(String::from).collect(),
+        slopes: report.slopes,
+    })
+}
+
+fn passports(raw_report: &str) -> Result<Inspection, Error> {
+    parse_report::<Vec<PassportRow>>(raw_report).map(Inspection::Passports)
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::{map, passports, policies, Inspection, PolicyRow};
+
+    #[test]
+    fn policy_table() {
+        let part_one = r#"[{"line":1,"password":"abcde","policy":"1-3 a","valid":true},
+            {"line":2,"password":"cdefg","policy":"1-3 b","valid":false}]"#;
+        let part_two = r#"[{"line":2,"password":"cdefg","policy":"1-3 b","valid":false},
+            {"line":1,"password":"abcde","policy":"1-3 a","valid":false}]"#;
+
+        let Inspection::Policies(rows) = policies(Some(part_one), Some(part_two)).unwrap() else {
+            panic!("day 2 is inspected as policies");
+        };
+        assert_eq!(
+            rows[0],
+            PolicyRow {
+                line: 1,
+                policy: String::from("1-3 a"),
+                password: String::from("abcde"),
+                valid: [Some(true), Some(false)],
+            }
+        );
+        assert_eq!(rows[1].valid, [Some(false), Some(false)]);
+
+        let Inspection::Policies(rows) = policies(None, Some(part_two)).unwrap() else {
+            panic!("day 2 is inspected as policies");
+        };
+        assert_eq!(rows[0].valid, [None, Some(false)]);
+        assert_eq!(
+            policies(Some("Correct passwords: 2"), None)
+                .unwrap_err()
+                .exit_code(),
+            4
+        );
+    }
+
+    #[test]
+    fn slopes() {
+        let raw_map = include_str!("../../examples/day03/map.txt");
+        let report = r#"{"slopes":[{"down":1,"path":[[1,3],[2,6]],"right":3,"trees":1}]}"#;
+
+        let Inspection::Map { lines, slopes } = map(raw_map, report).unwrap() else {
+            panic!("day 3 is inspected as a map");
+        };
+        assert_eq!(lines.len(), 11);
+        assert_eq!(
+            (slopes[0].right, slopes[0].down, slopes[0].trees),
+            (3, 1, 1)
+        );
+        assert_eq!(slopes[0].path, vec![(1, 3), (2, 6)]);
+    }
+
+    #[test]
+    fn passport_fields() {
+        let report = r#"[{"errors":[{"field":"hgt","message":"missing"},
+            {"field":"ecl","message":"'zzz' is not an allowed value"}],
+            "fields":[{"key":"byr","value":"1937"},{"key":"ecl","value":"zzz"}],
+            "first_line":1,"last_line":2,"passport":1,"token_errors":[],"valid":false}]"#;
+
+        let Inspection::Passports(rows) = passports(report).unwrap() else {
+            panic!("day 4 is inspected as passports");
+        };
+        assert_eq!(rows.len(), 1);
+        assert_eq!(
+            rows[0].field_statuses(),
+            vec![
+                ("byr", Some("1937"), None),
+                ("ecl", Some("zzz"), Some("'zzz' is not an allowed value")),
+                ("hgt", None, Some("missing")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,198 @@
+mod answers;
+mod bench;
+mod client;
+mod examples;
+mod inputs;
+mod inspect;
+#[cfg(test)]
+mod mock;
+mod registry;
+mod runner;
+mod scaffold;
+mod submit;
+mod tui;
+mod verify;
+mod watch;
+
+use aoc_common::Error;
+use client::{Client, Config};
+use inputs::Inputs;
+use registry::{parse_day, Registry};
+use std::path::Path;
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: aoc <command> [arguments]
+
+Commands:
+  bench [day] [--iterations N] [--threshold PERCENT] [--save-baseline]
+        build every solver of the day, or of all days, run it N times (20 by
+        default) and print timings of its phases; a median more than PERCENT
+        (10 by default) slower than the saved baseline is a regression
+  examples [day]
+        run solvers of the day, or of all days, on examples in examples/dayNN
+        and compare their output with the expected one
+  input <day> [--import FILE]
+        print the path of the cached input of the day, downloaded first if it
+        isn't cached yet, or imported from FILE
+  new <day> <slug>
+        create the crates day-NN/<slug> and day-NN/part-two of a new day from
+        the templates, with an empty day-NN/input.dat, and register them
+  submit <day> <part>
+        run the solver of the part and submit its answer; wrong answers are
+        never submitted again, the right one is saved in answers.toml
+  tui
+        show the dashboard with the last answer and timing of every part, run
+        parts and inspect intermediate state of days 2, 3 and 4
+  verify [day] [--record]
+        run every solver of the day, or of all days, and compare its answer
+        with the one known for its input; with --record unknown answers are
+        saved as correct
+  watch <day>
+        run the examples and then the input of the day every time its
+        sources, input or examples change
+
+Solvers read the cached input of their day, or day-NN/input.dat of their day.
+Inputs are downloaded into AOC_CACHE (.aoc-cache) and answers submitted with
+the session cookie in AOC_SESSION to AOC_ENDPOINT (https://adventofcode.com/2020),
+at most once per AOC_MIN_INTERVAL seconds (5).";
+
+fn main() -> ExitCode {
+    aoc_common::run(solve)
+}
+
+fn solve() -> Result<(), Error> {
+    // First argument is name of binary file.
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    match args.first().map(String::as_str) {
+        Some("bench") => {
+            let options = parse_bench_options(&args[1..])?;
+            let (registry, inputs) = load()?;
+            bench::bench(&registry, &inputs, &options)
+        }
+        Some("examples") => {
+            let day = match &args[1..] {
+                [] => None,
+                [day] => Some(parse_day(day)?),
+                _ => return Err(usage_error()),
+            };
+            examples::run_examples(&Registry::load()?, day)
+        }
+        Some("input") => {
+            let (_, inputs) = load()?;
+            let path = match &args[1..] {
+                [day] => inputs.fetch(parse_day(day)?)?,
+                [day, option, file_name] if option == "--import" => {
+                    inputs.import(parse_day(day)?, Path::new(file_name))?
+                }
+                _ => return Err(usage_error()),
+            };
+            println!("{}", path.display());
+            Ok(())
+        }
+        Some("new") => match &args[1..] {
+            [day, slug] => {
+                let dir = scaffold::new_day(&Registry::load()?, parse_day(day)?, slug)?;
+                println!("Created {}", dir.display());
+                Ok(())
+            }
+            _ => Err(usage_error()),
+        },
+        Some("submit") => match &args[1..] {
+            [day, part] => {
+                let part = match part.as_str() {
+                    "1" => 1,
+                    "2" => 2,
+                    _ => return Err(Error::Usage(format!("part must be 1 or 2: {}", part))),
+                };
+                let (registry, client, inputs) = load_with_client()?;
+                submit::submit(&registry, &inputs, &client, parse_day(day)?, part)
+            }
+            _ => Err(usage_error()),
+        },
+        Some("tui") => match &args[1..] {
+            [] => {
+                let (registry, client, inputs) = load_with_client()?;
+                let runs_file = client.session_dir().join(tui::RUNS_FILE);
+                tui::tui(registry, inputs, runs_file)
+            }
+            _ => Err(usage_error()),
+        },
+        Some("verify") => {
+            let options = parse_verify_options(&args[1..])?;
+            let (registry, inputs) = load()?;
+            verify::verify(&registry, &inputs, &options)
+        }
+        Some("watch") => match &args[1..] {
+            [day] => {
+                let (registry, inputs) = load()?;
+                watch::watch(&registry, &inputs, parse_day(day)?)
+            }
+            _ => Err(usage_error()),
+        },
+        _ => Err(usage_error()),
+    }
+}
+
+fn load() -> Result<(Registry, Inputs), Error> {
+    let (registry, _, inputs) = load_with_client()?;
+    Ok((registry, inputs))
+}
+
+fn load_with_client() -> Result<(Registry, Client, Inputs), Error> {
+    let registry = Registry::load()?;
+    let client = Client::new(Config::from_env(&registry.root)?);
+    let inputs = Inputs::new(client.clone());
+    Ok((registry, client, inputs))
+}
+
+fn parse_bench_options(args: &[String]) -> Result<bench::Options, Error> {
+    let mut options = bench::Options::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--iterations" => {
+                options.iterations = option_value(arg, args.next())?;
+            }
+            "--threshold" => {
+                options.threshold = option_value(arg, args.next())?;
+            }
+            "--save-baseline" => options.save_baseline = true,
+            day if options.day.is_none() && !day.starts_with("--") => {
+                options.day = Some(parse_day(day)?);
+            }
+            _ => return Err(usage_error()),
+        }
+    }
+    if options.threshold < 0.0 {
+        return Err(Error::Usage(String::from("threshold can't be negative")));
+    }
+    Ok(options)
+}
+
+fn parse_verify_options(args: &[String]) -> Result<verify::Options, Error> {
+    let mut options = verify::Options {
+        day: None,
+        record: false,
+    };
+    for arg in args {
+        match arg.as_str() {
+            "--record" => options.record = true,
+            day if options.day.is_none() && !day.starts_with("--") => {
+                options.day = Some(parse_day(day)?);
+            }
+            _ => return Err(usage_error()),
+        }
+    }
+    Ok(options)
+}
+
+fn option_value<T: std::str::FromStr>(option: &str, value: Option<&String>) -> Result<T, Error> {
+    let value = value.ok_or_else(usage_error)?;
+    value
+        .parse::<T>()
+        .map_err(|_| Error::Usage(format!("invalid value of {}: {}", option, value)))
+}
+
+fn usage_error() -> Error {
+    Error::Usage(String::from(USAGE))
+}
@@ -0,0 +1,187 @@
+use aoc_common::Error;
+use serde::Deserialize;
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// Name of the file with all solvers, in the root of the repository.
+pub const REGISTRY_FILE: &str = "days.toml";
+/// Name of the input file in the directory of every day, e.g. `day-04`,
+/// shared by both of its parts.
+pub const INPUT_FILE: &str = "input.dat";
+
+/// All solvers of the repository.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Registry {
+    /// Root of the repository, with the registry file.
+    pub root: PathBuf,
+    pub solvers: Vec<Solver>,
+}
+
+/// Single part of a single day.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Solver {
+    pub day: u8,
+    pub part: u8,
+    /// Directory of the crate, relative to the root of the repository.
+    pub path: String,
+    /// Arguments passed to the solver after the input.
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RegistryFile {
+    #[serde(rename = "solver", default)]
+    solvers: Vec<Solver>,
+}
+
+impl Registry {
+    /// Loads the registry from the first directory with the registry file,
+    /// starting at the current directory and going up.
+    pub fn load() -> Result<Registry, Error> {
+        let current_dir =
+            env::current_dir().map_err(|e| Error::io("unable to get current directory", e))?;
+        let root = current_dir
+            .ancestors()
+            .find(|dir| dir.join(REGISTRY_FILE).is_file())
+            .ok_or_else(|| {
+                Error::Usage(format!(
+                    "unable to find {} in {} or any parent directory",
+                    REGISTRY_FILE,
+                    current_dir.display()
+                ))
+            })?;
+        Registry::load_from(root)
+    }
+
+    pub fn load_from(root: &Path) -> Result<Registry, Error> {
+        let file_name = root.join(REGISTRY_FILE);
+        let raw_registry = std::fs::read_to_string(&file_name)
+            .map_err(|e| Error::io(format!("unable to read {}", file_name.display()), e))?;
+        let solvers = Registry::parse(&raw_registry)?;
+        Ok(Registry {
+            root: root.to_path_buf(),
+            solvers,
+        })
+    }
+
+    fn parse(raw_registry: &str) -> Result<Vec<Solver>, Error> {
+        let registry_file = toml::from_str::<RegistryFile>(raw_registry)
+            .map_err(|e| Error::Parse(format!("unable to parse {}, {}", REGISTRY_FILE, e)))?;
+        let mut solvers = registry_file.solvers;
+        for (i, solver) in solvers.iter().enumerate() {
+            if solvers[..i]
+                .iter()
+                .any(|other| (other.day, other.part) == (solver.day, solver.part))
+            {
+                return Err(Error::Validation(format!(
+                    "{} registers day {} part {} twice",
+                    REGISTRY_FILE, solver.day, solver.part
+                )));
+            }
+        }
+        solvers.sort_by_key(|solver| (solver.day, solver.part));
+        Ok(solvers)
+    }
+
+    /// Solvers of the given day, or all of them.
+    pub fn select(&self, day: Option<u8>) -> Result<Vec<&Solver>, Error> {
+        let solvers = self
+            .solvers
+            .iter()
+            .filter(|solver| day.is_none_or(|day| solver.day == day))
+            .collect::<Vec<&Solver>>();
+        match day {
+            Some(day) if solvers.is_empty() => Err(Error::Usage(format!(
+                "day {} is not registered in {}",
+                day, REGISTRY_FILE
+            ))),
+            _ => Ok(solvers),
+        }
+    }
+}
+
+impl Solver {
+    pub fn dir(&self, root: &Path) -> PathBuf {
+        root.join(&self.path)
+    }
+
+    /// Input of the day, the same for both parts.
+    pub fn input(&self, root: &Path) -> PathBuf {
+        root.join(format!("day-{:02}", self.day)).join(INPUT_FILE)
+    }
+
+    /// Short name used in reports, e.g. `day 3 part 2`.
+    pub fn name(&self) -> String {
+        format!("day {} part {}", self.day, self.part)
+    }
+}
+
+/// Reads the day from an optional argument.
+pub fn parse_day(argument: &str) -> Result<u8, Error> {
+    argument
+        .parse::<u8>()
+        .ok()
+        .filter(|day| (1..=25).contains(day))
+        .ok_or_else(|| Error::Usage(format!("day must be a number from 1 to 25: {}", argument)))
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::{parse_day, Registry};
+    use std::path::Path;
+
+    #[test]
+    fn parse_registry() {
+        let solvers = Registry::parse(
+            "[[solver]]\nday = 2\npart = 1\npath = \"day-02/a\"\n\
+             [[solver]]\nday = 1\npart = 2\npath = \"day-01/b\"\nargs = [\"-x\"]\n",
+        )
+        .unwrap();
+
+        assert_eq!(solvers.len(), 2);
+        assert_eq!(solvers[0].name(), "day 1 part 2");
+        assert_eq!(solvers[0].args, vec![String::from("-x")]);
+        assert!(solvers[1].args.is_empty());
+        assert_eq!(
+            solvers[1].input(Path::new("/repo")),
+            Path::new("/repo/day-02/input.dat")
+        );
+        assert_eq!(
+            solvers[0].input(Path::new("/repo")),
+            Path::new("/repo/day-01/input.dat")
+        );
+    }
+
+    #[test]
+    fn invalid_registry() {
+        let duplicate = "[[solver]]\nday = 1\npart = 1\npath = \"a\"\n\
+                         [[solver]]\nday = 1\npart = 1\npath = \"b\"\n";
+        assert_eq!(Registry::parse(duplicate).unwrap_err().exit_code(), 5);
+
+        let unknown_key = "[[solver]]\nday = 1\npart = 1\npath = \"a\"\nbin = \"a\"\n";
+        assert_eq!(Registry::parse(unknown_key).unwrap_err().exit_code(), 4);
+    }
+
+    #[test]
+    fn repository_registry() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+        let registry = Registry::load_from(root).unwrap();
+
+        assert_eq!(registry.select(Some(3)).unwrap().len(), 2);
+        assert!(registry.select(Some(25)).is_err());
+        for solver in registry.solvers.iter() {
+            assert!(solver.input(root).is_file(), "{}", solver.name());
+        }
+    }
+
+    #[test]
+    fn days() {
+        assert_eq!(parse_day("4").unwrap(), 4);
+        assert!(parse_day("0").is_err());
+        assert!(parse_day("26").is_err());
+        assert!(parse_day("x").is_err());
+    }
+}
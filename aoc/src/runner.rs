@@ -0,0 +1,145 @@
+use crate::registry::Solver;
+use aoc_common::{parse_phase_line, Error, TIMINGS_VAR};
+use serde_json::Value;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+/// Name of the phase which covers the whole run of the solver, measured by
+/// the runner, also the start of the process.
+pub const TOTAL_PHASE: &str = "total";
+
+/// Result of a single run of a solver.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Run {
+    /// Exit code of the solver, `None` if it was killed by a signal.
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    /// Stderr without lines with phase timings.
+    pub stderr: String,
+    /// Durations of the phases reported by the solver, in the order of
+    /// reporting.
+    pub phases: Vec<(String, Duration)>,
+    pub duration: Duration,
+}
+
+impl Run {
+    pub fn success(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+
+    /// Phases reported by the solver, followed by the whole run.
+    pub fn all_phases(&self) -> Vec<(String, Duration)> {
+        let mut phases = self.phases.clone();
+        phases.push((TOTAL_PHASE.to_string(), self.duration));
+        phases
+    }
+}
+
+/// Builds the solver in release mode and returns the path of its binary.
+///
+/// Every solver is built in its own target directory, also when a shared one
+/// is set by `CARGO_TARGET_DIR` or `build.target-dir`. Several solvers have
+/// the same package name, so in a shared directory they would overwrite the
+/// binary of each other, and an up-to-date solver isn't copied there again.
+pub fn build(root: &Path, solver: &Solver) -> Result<PathBuf, Error> {
+    let dir = solver.dir(root);
+    // `CARGO` is set when the runner itself is started by cargo.
+    let cargo = env::var("CARGO").unwrap_or_else(|_| String::from("cargo"));
+    let output = Command::new(cargo)
+        .args(["build", "--release", "--quiet"])
+        .arg("--message-format=json-render-diagnostics")
+        .arg("--manifest-path")
+        .arg(dir.join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(dir.join("target"))
+        .output()
+        .map_err(|e| Error::io("unable to start cargo", e))?;
+    if !output.status.success() {
+        return Err(Error::Check(format!(
+            "{}: build failed\n{}",
+            solver.name(),
+            String::from_utf8_lossy(&output.stderr).trim_end()
+        )));
+    }
+
+    let messages = String::from_utf8_lossy(&output.stdout);
+    find_executable(&messages).ok_or_else(|| {
+        Error::Check(format!(
+            "{}: build didn't produce any binary",
+            solver.name()
+        ))
+    })
+}
+
+/// Finds the binary in the JSON messages of cargo.
+fn find_executable(messages: &str) -> Option<PathBuf> {
+    messages
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter(|message| message["reason"] == "compiler-artifact")
+        .filter_map(|message| message["executable"].as_str().map(PathBuf::from))
+        .next_back()
+}
+
+/// Runs the binary of the solver on the given input. With `timings` the
+/// solver reports durations of its phases.
+pub fn run(executable: &Path, solver: &Solver, input: &Path, timings: bool) -> Result<Run, Error> {
+    let mut command = Command::new(executable);
+    command.arg(input).args(&solver.args);
+    if timings {
+        command.env(TIMINGS_VAR, "1");
+    } else {
+        command.env_remove(TIMINGS_VAR);
+    }
+
+    let start = Instant::now();
+    let output = command
+        .output()
+        .map_err(|e| Error::io(format!("unable to start {}", executable.display()), e))?;
+    let duration = start.elapsed();
+
+    let mut phases = Vec::new();
+    let mut stderr = String::new();
+    for line in String::from_utf8_lossy(&output.stderr).lines() {
+        match parse_phase_line(line) {
+            Some((name, nanos)) => phases.push((name.to_string(), Duration::from_nanos(nanos))),
+            None => {
+                stderr.push_str(line);
+                stderr.push('\n');
+            }
+        }
+    }
+    Ok(Run {
+        exit_code: output.status.code(),
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr,
+        phases,
+        duration,
+    })
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::find_executable;
+    use std::path::PathBuf;
+
+    #[test]
+    fn executable_from_messages() {
+        let messages = concat!(
+            r#"{"reason":"compiler-artifact","target":{"kind":["lib"]},"executable":null}"#,
+            "\n",
+            r#"{"reason":"compiler-artifact","target":{"kind":["bin"]},"executable":"/t/release/part-two"}"#,
+            "\n",
+            r#"{"reason":"build-finished","success":true}"#,
+            "\n",
+        );
+
+        assert_eq!(
+            find_executable(messages),
+            Some(PathBuf::from("/t/release/part-two"))
+        );
+        assert_eq!(find_executable(""), None);
+    }
+}
@@ -0,0 +1,230 @@
+use crate::examples::EXAMPLES_DIR;
+use crate::registry::{Registry, INPUT_FILE, REGISTRY_FILE};
+use aoc_common::Error;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Crate of the part one of a new day, relative to the directory of the day.
+const PART_ONE_DIR: &str = "{{slug}}";
+
+/// Crate of the part two of a new day, relative to the directory of the day.
+const PART_TWO_DIR: &str = "part-two";
+
+/// Files of a new day, relative to its directory, with their templates.
+const TEMPLATES: [(&str, &str); 6] = [
+    (
+        "{{slug}}/Cargo.toml",
+        include_str!("../templates/day/part-one/Cargo.toml"),
+    ),
+    (
+        "{{slug}}/README.md",
+        include_str!("../templates/day/part-one/README.md"),
+    ),
+    (
+        "{{slug}}/src/main.rs",
+        include_str!("../templates/day/part-one/main.rs"),
+    ),
+    (
+        "part-two/Cargo.toml",
+        include_str!("../templates/day/part-two/Cargo.toml"),
+    ),
+    (
+        "part-two/README.md",
+        include_str!("../templates/day/part-two/README.md"),
+    ),
+    (
+        "part-two/src/main.rs",
+        include_str!("../templates/day/part-two/main.rs"),
+    ),
+];
+
+/// Input of the example of a new day, relative to the directory of examples.
+/// Its expected outputs are added once the example is filled in.
+const EXAMPLE_FILE: &str = "example.txt";
+
+/// Creates the crates of both parts of a new day from the templates,
+/// `day-NN/<slug>` and `day-NN/part-two`, with an empty input of the day and
+/// an empty example in `examples/dayNN`, and registers them. Returns the
+/// directory of the day.
+pub fn new_day(registry: &Registry, day: u8, slug: &str) -> Result<PathBuf, Error> {
+    check_slug(slug)?;
+    if registry.solvers.iter().any(|solver| solver.day == day) {
+        return Err(Error::Usage(format!(
+            "day {} is already registered in {}",
+            day, REGISTRY_FILE
+        )));
+    }
+    let day_dir = format!("day-{:02}", day);
+    let dir = registry.root.join(&day_dir);
+    for crate_dir in [PART_ONE_DIR, PART_TWO_DIR] {
+        let crate_dir = dir.join(render(crate_dir, day, slug));
+        if crate_dir.exists() {
+            return Err(Error::Usage(format!(
+                "{} already exists",
+                crate_dir.display()
+            )));
+        }
+    }
+
+    for (file_name, template) in TEMPLATES {
+        let file_name = dir.join(render(file_name, day, slug));
+        // This `unwrap()` is safe because every file is inside the directory
+        // of the day.
+        let parent = file_name.parent().unwrap();
+        fs::create_dir_all(parent)
+            .map_err(|e| Error::io(format!("unable to create {}", parent.display()), e))?;
+        fs::write(&file_name, render(template, day, slug))
+            .map_err(|e| Error::io(format!("unable to write {}", file_name.display()), e))?;
+    }
+    // Input of the day is shared by its parts, one which is already there is
+    // kept.
+    let input = dir.join(INPUT_FILE);
+    if !input.exists() {
+        fs::write(&input, "")
+            .map_err(|e| Error::io(format!("unable to write {}", input.display()), e))?;
+    }
+    let examples_dir = registry
+        .root
+        .join(EXAMPLES_DIR)
+        .join(format!("day{:02}", day));
+    fs::create_dir_all(&examples_dir)
+        .map_err(|e| Error::io(format!("unable to create {}", examples_dir.display()), e))?;
+    // Example which is already there is kept.
+    let example = examples_dir.join(EXAMPLE_FILE);
+    if !example.exists() {
+        fs::write(&example, "")
+            .map_err(|e| Error::io(format!("unable to write {}", example.display()), e))?;
+    }
+    let paths = [PART_ONE_DIR, PART_TWO_DIR]
+        .map(|crate_dir| format!("{}/{}", day_dir, render(crate_dir, day, slug)));
+    register(&registry.root, day, &paths)?;
+    Ok(dir)
+}
+
+/// Slug is also the name of the crate, e.g. `toboggan-trajectory`.
+fn check_slug(slug: &str) -> Result<(), Error> {
+    let valid = slug.starts_with(|c: char| c.is_ascii_lowercase())
+        && slug
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && !slug.ends_with('-');
+    if !valid {
+        return Err(Error::Usage(format!(
+            "slug must start with a lowercase letter and contain only lowercase \
+             letters, digits and `-`: {}",
+            slug
+        )));
+    }
+    Ok(())
+}
+
+fn render(template: &str, day: u8, slug: &str) -> String {
+    template
+        .replace("{{day}}", &day.to_string())
+        .replace("{{padded_day}}", &format!("{:02}", day))
+        .replace("{{slug}}", slug)
+        .replace("{{title}}", &title(slug))
+}
+
+/// Title of the puzzle made of the slug, e.g. `Toboggan Trajectory`.
+fn title(slug: &str) -> String {
+    slug.split('-')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            // This `unwrap()` is safe because empty words are skipped.
+            let first = chars.next().unwrap();
+            first.to_ascii_uppercase().to_string() + chars.as_str()
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Appends both parts of the day, with the paths of their crates, to the
+/// registry file, which keeps its comments and order of the other solvers.
+fn register(root: &Path, day: u8, paths: &[String; 2]) -> Result<(), Error> {
+    let file_name = root.join(REGISTRY_FILE);
+    let mut entries = String::new();
+    for (part, path) in (1..).zip(paths) {
+        entries.push_str(&format!(
+            "\n[[solver]]\nday = {}\npart = {}\npath = \"{}\"\n",
+            day, part, path
+        ));
+    }
+    OpenOptions::new()
+        .append(true)
+        .open(&file_name)
+        .and_then(|mut file| file.write_all(entries.as_bytes()))
+        .map_err(|e| Error::io(format!("unable to write {}", file_name.display()), e))
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::{new_day, render, title};
+    use crate::registry::{Registry, REGISTRY_FILE};
+    use std::fs;
+
+    #[test]
+    fn templates() {
+        assert_eq!(title("toboggan-trajectory"), "Toboggan Trajectory");
+        assert_eq!(title("day5"), "Day5");
+        assert_eq!(
+            render("# Day {{day}}: {{title}} ({{slug}})", 5, "binary-boarding"),
+            "# Day 5: Binary Boarding (binary-boarding)"
+        );
+        assert_eq!(
+            render("examples/day{{padded_day}}", 5, "a"),
+            "examples/day05"
+        );
+    }
+
+    #[test]
+    fn new_days() {
+        let root = std::env::temp_dir().join(format!("aoc-new-day-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(
+            root.join(REGISTRY_FILE),
+            "# Solvers.\n\n[[solver]]\nday = 1\npart = 1\npath = \"day-01/a\"\n",
+        )
+        .unwrap();
+        let registry = Registry::load_from(&root).unwrap();
+
+        let dir = new_day(&registry, 5, "binary-boarding").unwrap();
+        assert_eq!(dir, root.join("day-05"));
+        for (crate_dir, name, part) in [
+            ("binary-boarding", "binary-boarding", "part_one"),
+            ("part-two", "part-two", "part_two"),
+        ] {
+            let crate_dir = dir.join(crate_dir);
+            let manifest = fs::read_to_string(crate_dir.join("Cargo.toml")).unwrap();
+            assert!(manifest.contains(&format!("name = \"{}\"", name)));
+            assert!(!crate_dir.join("input.dat").exists());
+            let main = fs::read_to_string(crate_dir.join("src/main.rs")).unwrap();
+            assert!(main.contains("include_str!(\"../../../examples/day05/example.txt\")"));
+            assert!(main.contains(&format!("fn {}(", part)));
+        }
+        assert_eq!(fs::read_to_string(dir.join("input.dat")).unwrap(), "");
+        assert_eq!(
+            fs::read_to_string(root.join("examples/day05/example.txt")).unwrap(),
+            ""
+        );
+
+        let registry = Registry::load_from(&root).unwrap();
+        let solvers = registry.select(Some(5)).unwrap();
+        assert_eq!(solvers.len(), 2);
+        assert_eq!(solvers[0].path, "day-05/binary-boarding");
+        assert_eq!(solvers[1].path, "day-05/part-two");
+        assert!(solvers[1].args.is_empty());
+        assert!(fs::read_to_string(root.join(REGISTRY_FILE))
+            .unwrap()
+            .starts_with("# Solvers.\n"));
+
+        assert_eq!(new_day(&registry, 5, "other").unwrap_err().exit_code(), 2);
+        assert_eq!(new_day(&registry, 1, "a").unwrap_err().exit_code(), 2);
+        assert_eq!(new_day(&registry, 6, "Day-6").unwrap_err().exit_code(), 2);
+        assert_eq!(new_day(&registry, 6, "day-").unwrap_err().exit_code(), 2);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,469 @@
+use crate::answers::{Answers, ANSWERS_FILE};
+use crate::client::{since_epoch, Client};
+use crate::inputs::Inputs;
+use crate::registry::Registry;
+use crate::verify;
+use aoc_common::Error;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use std::time::Duration;
+
+/// Name of the file with all submitted answers, in the directory of the
+/// session in the cache.
+pub const HISTORY_FILE: &str = "submissions.toml";
+
+/// Verdict of the server about a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong answer, without a hint.
+    Wrong,
+    /// Answer was sent too soon after the previous one, it wasn't checked.
+    RateLimited,
+    /// Part is solved already, the answer wasn't checked.
+    AlreadySolved,
+}
+
+/// Submitted answer with the verdict.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Submission {
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub verdict: Verdict,
+    /// Time of the submission, in seconds since the Unix epoch.
+    pub time: u64,
+    /// Time when the next answer can be submitted, in seconds since the Unix
+    /// epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_at: Option<u64>,
+}
+
+/// All answers submitted in a session.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct History {
+    #[serde(rename = "submission", default)]
+    pub submissions: Vec<Submission>,
+}
+
+impl Verdict {
+    fn is_wrong(&self) -> bool {
+        matches!(self, Verdict::TooHigh | Verdict::TooLow | Verdict::Wrong)
+    }
+}
+
+impl History {
+    /// Loads the history from the file. Missing file is an empty history.
+    pub fn load(file_name: &Path) -> Result<History, Error> {
+        if !file_name.exists() {
+            return Ok(History::default());
+        }
+        let raw_history = std::fs::read_to_string(file_name)
+            .map_err(|e| Error::io(format!("unable to read {}", file_name.display()), e))?;
+        toml::from_str::<History>(&raw_history)
+            .map_err(|e| Error::Parse(format!("unable to parse {}, {}", file_name.display(), e)))
+    }
+
+    pub fn save(&self, file_name: &Path) -> Result<(), Error> {
+        // This `unwrap()` is safe because the history contains only numbers
+        // and strings.
+        let raw_history = toml::to_string(self).unwrap();
+        if let Some(dir) = file_name.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| Error::io(format!("unable to create {}", dir.display()), e))?;
+        }
+        std::fs::write(file_name, raw_history)
+            .map_err(|e| Error::io(format!("unable to write {}", file_name.display()), e))
+    }
+
+    /// Explains why the answer must not be submitted at the given time: the
+    /// part is solved, the answer was wrong before or it is out of the range
+    /// of earlier hints, or the server asked to wait.
+    pub fn check(&self, day: u8, part: u8, answer: &str, now: u64) -> Result<(), String> {
+        let submissions = self
+            .submissions
+            .iter()
+            .filter(|submission| submission.day == day && submission.part == part)
+            .collect::<Vec<&Submission>>();
+
+        if let Some(correct) = submissions
+            .iter()
+            .find(|submission| submission.verdict == Verdict::Correct)
+        {
+            return Err(format!(
+                "day {} part {} is already solved with {}",
+                day, part, correct.answer
+            ));
+        }
+        if let Some(wrong) = submissions
+            .iter()
+            .find(|submission| submission.answer == answer && submission.verdict.is_wrong())
+        {
+            return Err(format!(
+                "{} was already submitted, it was {}",
+                answer, wrong.verdict
+            ));
+        }
+        if let Ok(value) = answer.parse::<i128>() {
+            for submission in submissions.iter() {
+                let Ok(hint) = submission.answer.parse::<i128>() else {
+                    continue;
+                };
+                let out_of_range = match submission.verdict {
+                    Verdict::TooHigh => value >= hint,
+                    Verdict::TooLow => value <= hint,
+                    _ => false,
+                };
+                if out_of_range {
+                    return Err(format!(
+                        "{} can't be right, {} was {}",
+                        answer, hint, submission.verdict
+                    ));
+                }
+            }
+        }
+        if let Some(retry_at) = submissions
+            .iter()
+            .filter_map(|submission| submission.retry_at)
+            .max()
+            .filter(|retry_at| *retry_at > now)
+        {
+            return Err(format!(
+                "server asked to wait, the answer can be submitted in {} s",
+                retry_at - now
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Reads the verdict from the page sent by the server, with the time to wait
+/// before the next submission. The page is returned as a plain text if it
+/// has no known verdict.
+pub fn parse_response(page: &str) -> Result<(Verdict, Option<Duration>), String> {
+    let text = plain_text(page);
+    let verdict = if text.contains("That's the right answer") {
+        Verdict::Correct
+    } else if text.contains("That's not the right answer") {
+        if text.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if text.contains("your answer is too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Wrong
+        }
+    } else if text.contains("You gave an answer too recently") {
+        Verdict::RateLimited
+    } else if text.contains("You don't seem to be solving the right level") {
+        Verdict::AlreadySolved
+    } else {
+        return Err(text);
+    };
+    Ok((verdict, parse_wait(&text)))
+}
+
+/// Text of the article with the verdict, without HTML tags and with single
+/// spaces.
+fn plain_text(page: &str) -> String {
+    let article = match (page.find("<article"), page.find("</article>")) {
+        (Some(start), Some(end)) if start < end => &page[start..end],
+        _ => page,
+    };
+    let mut text = String::with_capacity(article.len());
+    let mut in_tag = false;
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            _ if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Time to wait, e.g. `You have 1m 5s left to wait` or `Please wait one
+/// minute before trying again`.
+fn parse_wait(text: &str) -> Option<Duration> {
+    if let Some((_, rest)) = text.split_once("You have ") {
+        let (left, _) = rest.split_once(" left to wait")?;
+        let mut seconds = 0;
+        for amount in left.split_whitespace() {
+            let unit = match amount.chars().last()? {
+                'h' => 3600,
+                'm' => 60,
+                's' => 1,
+                _ => return None,
+            };
+            seconds += amount[..amount.len() - 1].parse::<u64>().ok()? * unit;
+        }
+        return Some(Duration::from_secs(seconds));
+    }
+    let (_, rest) = text.split_once("lease wait ")?;
+    let minutes = match rest.split_whitespace().next()? {
+        "one" => 1,
+        amount => amount.parse::<u64>().ok()?,
+    };
+    Some(Duration::from_secs(minutes * 60))
+}
+
+/// Runs the solver of the part on its input and submits its answer, unless
+/// the history or the known answers tell it is wrong. The right answer is
+/// saved as known, other verdicts are reported as an error.
+pub fn submit(
+    registry: &Registry,
+    inputs: &Inputs,
+    client: &Client,
+    day: u8,
+    part: u8,
+) -> Result<(), Error> {
+    let solver = registry
+        .select(Some(day))?
+        .into_iter()
+        .find(|solver| solver.part == part)
+        .ok_or_else(|| Error::Usage(format!("day {} part {} is not registered", day, part)))?;
+    let (input_hash, answer) = verify::solve(registry, inputs, solver)?;
+
+    let answers_file = registry.root.join(ANSWERS_FILE);
+    let mut answers = Answers::load(&answers_file)?;
+    match answers.get(solver, &input_hash) {
+        Some(known) if known == answer => {
+            return Err(Error::Check(format!(
+                "{} is already known as the right answer in {}",
+                answer, ANSWERS_FILE
+            )))
+        }
+        Some(known) => {
+            return Err(Error::Check(format!(
+                "{} is wrong, the right answer in {} is {}",
+                answer, ANSWERS_FILE, known
+            )))
+        }
+        None => (),
+    }
+
+    let submission = send(client, day, part, &answer)?;
+    let message = format!("{}: {} is {}", solver.name(), answer, submission.verdict);
+    if submission.verdict != Verdict::Correct {
+        let wait = submission
+            .retry_at
+            .map(|retry_at| {
+                format!(
+                    ", next answer can be submitted in {} s",
+                    retry_at - submission.time
+                )
+            })
+            .unwrap_or_default();
+        return Err(Error::Check(message + &wait));
+    }
+    answers.insert(solver, &input_hash, &answer);
+    answers.save(&answers_file)?;
+    println!("{}, saved to {}", message, ANSWERS_FILE);
+    Ok(())
+}
+
+/// Submits the answer, unless the history of the session tells it is wrong,
+/// and adds it to the history with the verdict of the server.
+pub fn send(client: &Client, day: u8, part: u8, answer: &str) -> Result<Submission, Error> {
+    let history_file = client.session_dir().join(HISTORY_FILE);
+    let mut history = History::load(&history_file)?;
+    let now = since_epoch().as_secs();
+    history
+        .check(day, part, answer, now)
+        .map_err(|reason| Error::Check(format!("{} wasn't submitted, {}", answer, reason)))?;
+
+    let offline_hint = format!("submit {} on the website", answer);
+    let page = client.post(
+        &format!("/day/{}/answer", day),
+        &[("level", &part.to_string()), ("answer", answer)],
+        &offline_hint,
+    )?;
+    let (verdict, wait) = parse_response(&page).map_err(|text| {
+        Error::Check(format!(
+            "unknown response of the server to {}: {}",
+            answer, text
+        ))
+    })?;
+
+    let submission = Submission {
+        day,
+        part,
+        answer: answer.to_string(),
+        verdict,
+        time: now,
+        retry_at: wait.map(|wait| now + wait.as_secs()),
+    };
+    history.submissions.push(submission.clone());
+    history.save(&history_file)?;
+    Ok(submission)
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let verdict = match self {
+            Verdict::Correct => "correct",
+            Verdict::TooHigh => "too high",
+            Verdict::TooLow => "too low",
+            Verdict::Wrong => "wrong",
+            Verdict::RateLimited => "rate limited",
+            Verdict::AlreadySolved => "already solved",
+        };
+        write!(f, "{}", verdict)
+    }
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::{parse_response, send, History, Submission, Verdict, HISTORY_FILE};
+    use crate::client::Client;
+    use crate::mock::{test_config, MockServer, Response};
+    use std::time::Duration;
+
+    const CORRECT: &str = "<html><main><article><p>That's the right answer!  You are \
+                           <span class=\"day-success\">one gold star</span> closer.</p></article></main></html>";
+    const TOO_HIGH: &str = "<article><p>That's not the right answer; your answer is too high.  \
+                            Please wait one minute before trying again.</p></article>";
+    const TOO_LOW: &str = "<article><p>That's not the right answer; your answer is too low.  \
+                           Please wait 5 minutes before trying again.</p></article>";
+    const RATE_LIMITED: &str = "<article><p>You gave an answer too recently; you have to wait \
+                                after submitting an answer before trying again.  You have 1m 5s \
+                                left to wait.</p></article>";
+    const ALREADY_SOLVED: &str = "<article><p>You don't seem to be solving the right level.  \
+                                  Did you already complete it?</p></article>";
+
+    fn submission(answer: &str, verdict: Verdict, retry_at: Option<u64>) -> Submission {
+        Submission {
+            day: 1,
+            part: 1,
+            answer: answer.to_string(),
+            verdict,
+            time: 100,
+            retry_at,
+        }
+    }
+
+    #[test]
+    fn responses() {
+        assert_eq!(parse_response(CORRECT), Ok((Verdict::Correct, None)));
+        assert_eq!(
+            parse_response(TOO_HIGH),
+            Ok((Verdict::TooHigh, Some(Duration::from_secs(60))))
+        );
+        assert_eq!(
+            parse_response(TOO_LOW),
+            Ok((Verdict::TooLow, Some(Duration::from_secs(300))))
+        );
+        assert_eq!(
+            parse_response(RATE_LIMITED),
+            Ok((Verdict::RateLimited, Some(Duration::from_secs(65))))
+        );
+        assert_eq!(
+            parse_response(ALREADY_SOLVED),
+            Ok((Verdict::AlreadySolved, None))
+        );
+        assert_eq!(
+            parse_response("<p>Something <b>else</b></p>"),
+            Err(String::from("Something else"))
+        );
+    }
+
+    #[test]
+    fn history() {
+        let history = History {
+            submissions: vec![
+                submission("900", Verdict::TooHigh, Some(160)),
+                submission("100", Verdict::TooLow, None),
+            ],
+        };
+        assert_eq!(history.check(1, 1, "500", 200), Ok(()));
+        assert_eq!(
+            history.check(1, 1, "500", 150),
+            Err(String::from(
+                "server asked to wait, the answer can be submitted in 10 s"
+            ))
+        );
+        assert_eq!(
+            history.check(1, 1, "900", 200),
+            Err(String::from("900 was already submitted, it was too high"))
+        );
+        assert_eq!(
+            history.check(1, 1, "950", 200),
+            Err(String::from("950 can't be right, 900 was too high"))
+        );
+        assert_eq!(
+            history.check(1, 1, "99", 200),
+            Err(String::from("99 can't be right, 100 was too low"))
+        );
+        assert_eq!(history.check(1, 2, "900", 200), Ok(()));
+
+        let mut solved = history.clone();
+        solved
+            .submissions
+            .push(submission("500", Verdict::Correct, None));
+        assert_eq!(
+            solved.check(1, 1, "500", 200),
+            Err(String::from("day 1 part 1 is already solved with 500"))
+        );
+
+        let saved = toml::to_string(&solved).unwrap();
+        assert!(saved.contains("verdict = \"too-high\""));
+        assert_eq!(toml::from_str::<History>(&saved).unwrap(), solved);
+    }
+
+    #[test]
+    fn mock_server() {
+        let server =
+            MockServer::start(
+                |request| match (request.method.as_str(), request.path.as_str()) {
+                    ("POST", "/day/1/answer") if request.body == "level=1&answer=514579" => {
+                        Response::new(200, CORRECT)
+                    }
+                    ("POST", "/day/1/answer") => Response::new(200, TOO_LOW),
+                    _ => Response::new(404, "Not found"),
+                },
+            );
+        let config = test_config("submit", server.url(), Some("secret"));
+        let cache_dir = config.cache_dir.clone();
+        let client = Client::new(config);
+
+        let submission = send(&client, 1, 1, "1").unwrap();
+        assert_eq!(submission.verdict, Verdict::TooLow);
+        assert_eq!(submission.retry_at, Some(submission.time + 300));
+        // Wrong answer is never sent again, also after the wait.
+        let error = send(&client, 1, 1, "1").unwrap_err();
+        assert_eq!(error.exit_code(), 1);
+        assert_eq!(
+            error.to_string(),
+            "1 wasn't submitted, 1 was already submitted, it was too low"
+        );
+        assert_eq!(server.requests().len(), 1);
+
+        let history_file = client.session_dir().join(HISTORY_FILE);
+        let mut history = History::load(&history_file).unwrap();
+        history.submissions[0].retry_at = None;
+        history.save(&history_file).unwrap();
+        assert_eq!(
+            send(&client, 1, 1, "514579").unwrap().verdict,
+            Verdict::Correct
+        );
+        assert_eq!(History::load(&history_file).unwrap().submissions.len(), 2);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].header("cookie"), Some("session=secret"));
+        assert_eq!(
+            requests[1].header("content-type"),
+            Some("application/x-www-form-urlencoded")
+        );
+        std::fs::remove_dir_all(&cache_dir).unwrap();
+    }
+}
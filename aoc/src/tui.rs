@@ -0,0 +1,892 @@
+use crate::answers::{Answers, ANSWERS_FILE};
+use crate::bench::format_duration;
+use crate::client::since_epoch;
+use crate::inputs::Inputs;
+use crate::inspect::{self, Inspection, PassportRow, PolicyRow, Slope};
+use crate::registry::{Registry, Solver};
+use crate::runner;
+use crate::verify::{self, Outcome};
+use aoc_common::Error;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+
+/// Name of the file with the last run of every part, in the directory of the
+/// session in the cache.
+pub const RUNS_FILE: &str = "runs.toml";
+
+/// Rows moved by Page Up and Page Down.
+const PAGE: usize = 20;
+
+/// Last run of a part, shown also in the next sessions of the dashboard.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LastRun {
+    pub day: u8,
+    pub part: u8,
+    /// Status of the answer, e.g. `PASS`.
+    pub status: String,
+    /// Answer of the solver, or what went wrong.
+    pub answer: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ns: Option<u64>,
+    /// Time of the run, in seconds since the Unix epoch.
+    pub time: u64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LastRuns {
+    #[serde(rename = "run", default)]
+    pub runs: Vec<LastRun>,
+}
+
+/// Work done in the background, so the dashboard doesn't freeze while a
+/// solver is built.
+#[derive(Debug, Clone, PartialEq)]
+enum Job {
+    Run(Solver),
+    Inspect(u8),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Done {
+    Run {
+        day: u8,
+        part: u8,
+        outcome: Outcome,
+        duration: Option<Duration>,
+    },
+    Inspect {
+        day: u8,
+        inspection: Result<Inspection, String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum View {
+    Days,
+    /// Inspector of the day. `selected` is the selected row, or the first
+    /// shown line of the map.
+    Inspector {
+        day: u8,
+        inspection: Inspection,
+        selected: usize,
+        slope: usize,
+    },
+}
+
+/// State of the dashboard.
+pub struct App {
+    solvers: Vec<Solver>,
+    runs: LastRuns,
+    runs_file: PathBuf,
+    /// Index of the selected solver.
+    selected: usize,
+    /// Parts waiting for their run, the first one is running.
+    pending: Vec<(u8, u8)>,
+    view: View,
+    message: String,
+    quit: bool,
+}
+
+impl LastRun {
+    fn new(day: u8, part: u8, outcome: &Outcome, duration: Option<Duration>, time: u64) -> Self {
+        LastRun {
+            day,
+            part,
+            status: outcome.status().to_string(),
+            answer: outcome.details(),
+            duration_ns: duration.map(|duration| duration.as_nanos() as u64),
+            time,
+        }
+    }
+}
+
+impl LastRuns {
+    /// Loads the runs from the file. Missing file means no runs.
+    pub fn load(file_name: &Path) -> Result<LastRuns, Error> {
+        if !file_name.exists() {
+            return Ok(LastRuns::default());
+        }
+        let raw_runs = std::fs::read_to_string(file_name)
+            .map_err(|e| Error::io(format!("unable to read {}", file_name.display()), e))?;
+        toml::from_str::<LastRuns>(&raw_runs)
+            .map_err(|e| Error::Parse(format!("unable to parse {}, {}", file_name.display(), e)))
+    }
+
+    pub fn save(&self, file_name: &Path) -> Result<(), Error> {
+        // This `unwrap()` is safe because the runs contain only numbers and
+        // strings.
+        let raw_runs = toml::to_string(self).unwrap();
+        if let Some(dir) = file_name.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| Error::io(format!("unable to create {}", dir.display()), e))?;
+        }
+        std::fs::write(file_name, raw_runs)
+            .map_err(|e| Error::io(format!("unable to write {}", file_name.display()), e))
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&LastRun> {
+        self.runs
+            .iter()
+            .find(|run| (run.day, run.part) == (day, part))
+    }
+
+    /// Replaces the earlier run of the part.
+    pub fn insert(&mut self, run: LastRun) {
+        self.runs
+            .retain(|other| (other.day, other.part) != (run.day, run.part));
+        self.runs.push(run);
+        self.runs.sort_by_key(|run| (run.day, run.part));
+    }
+}
+
+/// Time since an event, e.g. `5 min ago`.
+pub fn format_age(seconds: u64) -> String {
+    match seconds {
+        0..=59 => String::from("just now"),
+        60..=3_599 => format!("{} min ago", seconds / 60),
+        3_600..=86_399 => format!("{} h ago", seconds / 3_600),
+        _ => format!("{} d ago", seconds / 86_400),
+    }
+}
+
+/// Shows the dashboard until the user quits.
+pub fn tui(registry: Registry, inputs: Inputs, runs_file: PathBuf) -> Result<(), Error> {
+    if !std::io::stdout().is_terminal() {
+        return Err(Error::Usage(String::from("aoc tui must run in a terminal")));
+    }
+    let runs = LastRuns::load(&runs_file)?;
+    let mut app = App::new(registry.solvers.clone(), runs, runs_file);
+    let (jobs, done) = spawn_worker(registry, inputs);
+
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal, &jobs, &done);
+    ratatui::restore();
+    result
+}
+
+/// Starts the thread which does the jobs one by one.
+fn spawn_worker(registry: Registry, inputs: Inputs) -> (Sender<Job>, Receiver<Done>) {
+    let (jobs, job_receiver) = mpsc::channel::<Job>();
+    let (done_sender, done) = mpsc::channel::<Done>();
+    thread::spawn(move || {
+        for job in job_receiver {
+            let result = match job {
+                Job::Run(solver) => {
+                    let (outcome, duration) = run_part(&registry, &inputs, &solver);
+                    Done::Run {
+                        day: solver.day,
+                        part: solver.part,
+                        outcome,
+                        duration,
+                    }
+                }
+                Job::Inspect(day) => Done::Inspect {
+                    day,
+                    inspection: inspect::inspect(&registry, &inputs, day)
+                        .map_err(|e| e.to_string()),
+                },
+            };
+            // The dashboard is closed when nobody receives the result.
+            if done_sender.send(result).is_err() {
+                break;
+            }
+        }
+    });
+    (jobs, done)
+}
+
+fn run_part(registry: &Registry, inputs: &Inputs, solver: &Solver) -> (Outcome, Option<Duration>) {
+    let executable = runner::build(&registry.root, solver);
+    let answers = Answers::load(&registry.root.join(ANSWERS_FILE));
+    match (executable, answers) {
+        (Ok(executable), Ok(answers)) => {
+            verify::outcome(registry, inputs, solver, &executable, &answers)
+        }
+        (Err(e), _) | (_, Err(e)) => (Outcome::Error(e.to_string()), None),
+    }
+}
+
+impl App {
+    pub fn new(solvers: Vec<Solver>, runs: LastRuns, runs_file: PathBuf) -> App {
+        App {
+            solvers,
+            runs,
+            runs_file,
+            selected: 0,
+            pending: Vec::new(),
+            view: View::Days,
+            message: String::new(),
+            quit: false,
+        }
+    }
+
+    fn run(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        jobs: &Sender<Job>,
+        done: &Receiver<Done>,
+    ) -> Result<(), Error> {
+        while !self.quit {
+            let now = since_epoch().as_secs();
+            terminal
+                .draw(|frame| self.draw(frame, now))
+                .map_err(|e| Error::io("unable to draw the dashboard", e))?;
+
+            // Results of the jobs are checked at least ten times a second.
+            let ready = event::poll(Duration::from_millis(100))
+                .map_err(|e| Error::io("unable to read keys", e))?;
+            if ready {
+                if let Event::Key(key) =
+                    event::read().map_err(|e| Error::io("unable to read keys", e))?
+                {
+                    if key.kind == KeyEventKind::Press {
+                        for job in self.on_key(key.code) {
+                            // This `unwrap()` is safe because the worker
+                            // runs as long as its receiver exists.
+                            jobs.send(job).unwrap();
+                        }
+                    }
+                }
+            }
+            while let Ok(result) = done.try_recv() {
+                self.on_done(result);
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles the key and returns jobs to do.
+    fn on_key(&mut self, key: KeyCode) -> Vec<Job> {
+        match &mut self.view {
+            View::Days => self.on_days_key(key),
+            View::Inspector {
+                day,
+                inspection,
+                selected,
+                slope,
+            } => {
+                let (rows, slopes) = match inspection {
+                    Inspection::Policies(rows) => (rows.len(), 0),
+                    Inspection::Map { lines, slopes } => (lines.len(), slopes.len()),
+                    Inspection::Passports(rows) => (rows.len(), 0),
+                };
+                let last_row = rows.saturating_sub(1);
+                match key {
+                    KeyCode::Esc | KeyCode::Char('q') => self.view = View::Days,
+                    KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+                    KeyCode::Down | KeyCode::Char('j') => *selected = (*selected + 1).min(last_row),
+                    KeyCode::PageUp => *selected = selected.saturating_sub(PAGE),
+                    KeyCode::PageDown => *selected = (*selected + PAGE).min(last_row),
+                    KeyCode::Left if slopes > 0 => *slope = (*slope + slopes - 1) % slopes,
+                    KeyCode::Right if slopes > 0 => *slope = (*slope + 1) % slopes,
+                    KeyCode::Char('r') => {
+                        self.message = format!("Loading inspector of day {}...", day);
+                        return vec![Job::Inspect(*day)];
+                    }
+                    _ => (),
+                }
+                Vec::new()
+            }
+        }
+    }
+
+    fn on_days_key(&mut self, key: KeyCode) -> Vec<Job> {
+        let Some(solver) = self.solvers.get(self.selected) else {
+            if matches!(key, KeyCode::Esc | KeyCode::Char('q')) {
+                self.quit = true;
+            }
+            return Vec::new();
+        };
+        match key {
+            KeyCode::Esc | KeyCode::Char('q') => self.quit = true,
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1).min(self.solvers.len() - 1)
+            }
+            KeyCode::Enter | KeyCode::Char('r') => return self.queue(vec![solver.clone()]),
+            KeyCode::Char('a') => return self.queue(self.solvers.clone()),
+            KeyCode::Char('i') if inspect::DAYS.contains(&solver.day) => {
+                self.message = format!("Loading inspector of day {}...", solver.day);
+                return vec![Job::Inspect(solver.day)];
+            }
+            KeyCode::Char('i') => {
+                self.message = format!("Day {} doesn't have an inspector", solver.day);
+            }
+            _ => (),
+        }
+        Vec::new()
+    }
+
+    /// Runs of the solvers which aren't waiting already.
+    fn queue(&mut self, solvers: Vec<Solver>) -> Vec<Job> {
+        let mut jobs = Vec::new();
+        for solver in solvers {
+            if !self.pending.contains(&(solver.day, solver.part)) {
+                self.pending.push((solver.day, solver.part));
+                jobs.push(Job::Run(solver));
+            }
+        }
+        jobs
+    }
+
+    fn on_done(&mut self, done: Done) {
+        match done {
+            Done::Run {
+                day,
+                part,
+                outcome,
+                duration,
+            } => {
+                self.pending.retain(|pending| *pending != (day, part));
+                let time = since_epoch().as_secs();
+                self.runs
+                    .insert(LastRun::new(day, part, &outcome, duration, time));
+                self.message = match self.runs.save(&self.runs_file) {
+                    Ok(()) => format!("Day {} part {}: {}", day, part, outcome.status()),
+                    Err(e) => e.to_string(),
+                };
+            }
+            Done::Inspect { day, inspection } => match inspection {
+                Ok(inspection) => {
+                    self.message.clear();
+                    self.view = View::Inspector {
+                        day,
+                        inspection,
+                        selected: 0,
+                        slope: 0,
+                    };
+                }
+                Err(message) => self.message = message,
+            },
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame, now: u64) {
+        let [body, message, help] = Layout::vertical([
+            Constraint::Min(0),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let help_text = match &self.view {
+            View::Days => {
+                self.draw_days(frame, body, now);
+                "↑/↓ select  enter run  a run all  i inspect  q quit"
+            }
+            View::Inspector {
+                day,
+                inspection,
+                selected,
+                slope,
+            } => match inspection {
+                Inspection::Policies(rows) => {
+                    draw_policies(frame, body, *day, rows, *selected);
+                    "↑/↓ select  r reload  esc back"
+                }
+                Inspection::Map { lines, slopes } => {
+                    draw_map(frame, body, *day, lines, slopes.get(*slope), *selected);
+                    "↑/↓ scroll  ←/→ slope  r reload  esc back"
+                }
+                Inspection::Passports(rows) => {
+                    draw_passports(frame, body, *day, rows, *selected);
+                    "↑/↓ select  r reload  esc back"
+                }
+            },
+        };
+        // Only the first line of errors fits.
+        let first_line = self.message.lines().next().unwrap_or_default();
+        frame.render_widget(Paragraph::new(first_line), message);
+        frame.render_widget(
+            Paragraph::new(help_text).style(Style::new().add_modifier(Modifier::DIM)),
+            help,
+        );
+    }
+
+    fn draw_days(&self, frame: &mut Frame, area: Rect, now: u64) {
+        let rows = self.solvers.iter().map(|solver| {
+            let key = (solver.day, solver.part);
+            let run = self.runs.get(solver.day, solver.part);
+            let (status, color) = match self.pending.iter().position(|pending| *pending == key) {
+                Some(0) => (String::from("RUNNING"), Color::Cyan),
+                Some(_) => (String::from("QUEUED"), Color::Cyan),
+                None => match run {
+                    Some(run) => (run.status.clone(), status_color(&run.status)),
+                    None => (String::new(), Color::Reset),
+                },
+            };
+            let answer = run
+                .map(|run| run.answer.lines().next().unwrap_or_default().to_string())
+                .unwrap_or_default();
+            let duration = run
+                .and_then(|run| run.duration_ns)
+                .map(|nanos| format_duration(Duration::from_nanos(nanos)))
+                .unwrap_or_default();
+            let age = run
+                .map(|run| format_age(now.saturating_sub(run.time)))
+                .unwrap_or_default();
+            Row::new(vec![
+                Line::from(solver.day.to_string()),
+                Line::from(solver.part.to_string()),
+                Line::from(solver.path.clone()),
+                Line::styled(status, Style::new().fg(color)),
+                Line::from(answer),
+                Line::from(duration).right_aligned(),
+                Line::from(age).right_aligned(),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(3),
+                Constraint::Length(4),
+                Constraint::Length(26),
+                Constraint::Length(8),
+                Constraint::Min(12),
+                Constraint::Length(10),
+                Constraint::Length(11),
+            ],
+        )
+        .header(header(&[
+            "Day", "Part", "Crate", "Status", "Answer", "Time", "Last run",
+        ]))
+        .block(Block::bordered().title(" Advent of Code 2020 "))
+        .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        let mut state = TableState::new().with_selected(Some(self.selected));
+        frame.render_stateful_widget(table, area, &mut state);
+    }
+}
+
+fn header(titles: &[&'static str]) -> Row<'static> {
+    Row::new(titles.to_vec()).style(Style::new().add_modifier(Modifier::BOLD))
+}
+
+fn status_color(status: &str) -> Color {
+    match status {
+        "PASS" => Color::Green,
+        "UNKNOWN" => Color::Yellow,
+        _ => Color::Red,
+    }
+}
+
+fn validity(valid: Option<bool>) -> Line<'static> {
+    match valid {
+        Some(true) => Line::styled("pass", Style::new().fg(Color::Green)),
+        Some(false) => Line::styled("fail", Style::new().fg(Color::Red)),
+        None => Line::from("-"),
+    }
+}
+
+fn draw_policies(frame: &mut Frame, area: Rect, day: u8, rows: &[PolicyRow], selected: usize) {
+    let passed = |part: usize| {
+        rows.iter()
+            .filter(|row| row.valid[part] == Some(true))
+            .count()
+    };
+    let title = format!(
+        " Day {}: {} policies, {} pass part 1, {} pass part 2 ",
+        day,
+        rows.len(),
+        passed(0),
+        passed(1)
+    );
+    let table_rows = rows.iter().map(|row| {
+        Row::new(vec![
+            Line::from(row.line.to_string()).right_aligned(),
+            Line::from(row.policy.clone()),
+            Line::from(row.password.clone()),
+            validity(row.valid[0]),
+            validity(row.valid[1]),
+        ])
+    });
+    let table = Table::new(
+        table_rows,
+        [
+            Constraint::Length(6),
+            Constraint::Length(10),
+            Constraint::Min(20),
+            Constraint::Length(6),
+            Constraint::Length(6),
+        ],
+    )
+    .header(header(&["Line", "Policy", "Password", "Part 1", "Part 2"]))
+    .block(Block::bordered().title(title))
+    .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    let mut state = TableState::new().with_selected(Some(selected));
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+/// Map from line `first_line`, with trees hit on the slope shown as `X` and
+/// other visited fields as `O`.
+fn draw_map(
+    frame: &mut Frame,
+    area: Rect,
+    day: u8,
+    lines: &[String],
+    slope: Option<&Slope>,
+    first_line: usize,
+) {
+    let title = match slope {
+        Some(slope) => format!(
+            " Day {}: slope right {}, down {}, {} trees ",
+            day, slope.right, slope.down, slope.trees
+        ),
+        None => format!(" Day {}: no slopes ", day),
+    };
+    let path = slope
+        .map(|slope| {
+            slope
+                .path
+                .iter()
+                .copied()
+                .collect::<BTreeMap<usize, usize>>()
+        })
+        .unwrap_or_default();
+
+    let map_lines = lines
+        .iter()
+        .enumerate()
+        .skip(first_line)
+        .take(area.height as usize)
+        .map(|(line_number, line)| {
+            let Some(&column) = path.get(&line_number) else {
+                return Line::from(line.clone());
+            };
+            // Columns count characters, symbols of the map don't have to be
+            // ASCII.
+            let index = line
+                .char_indices()
+                .nth(column)
+                .map_or(line.len(), |(index, _)| index);
+            let (before, rest) = line.split_at(index);
+            let mut rest = rest.chars();
+            let field = match rest.next() {
+                Some('#') => Span::styled(
+                    "X",
+                    Style::new().fg(Color::Red).add_modifier(Modifier::BOLD),
+                ),
+                Some(_) => Span::styled(
+                    "O",
+                    Style::new().fg(Color::Green).add_modifier(Modifier::BOLD),
+                ),
+                None => Span::raw(""),
+            };
+            Line::from(vec![
+                Span::raw(before.to_string()),
+                field,
+                Span::raw(rest.collect::<String>()),
+            ])
+        })
+        .collect::<Vec<Line>>();
+    frame.render_widget(
+        Paragraph::new(map_lines).block(Block::bordered().title(title)),
+        area,
+    );
+}
+
+fn draw_passports(frame: &mut Frame, area: Rect, day: u8, rows: &[PassportRow], selected: usize) {
+    let [list, details] =
+        Layout::horizontal([Constraint::Length(30), Constraint::Min(0)]).areas(area);
+
+    let valid = rows.iter().filter(|row| row.valid).count();
+    let title = format!(" Day {}: {} of {} valid ", day, valid, rows.len());
+    let passports = rows.iter().map(|row| {
+        Row::new(vec![
+            Line::from(row.passport.to_string()).right_aligned(),
+            Line::from(format!("{}-{}", row.first_line, row.last_line)),
+            validity(Some(row.valid)),
+        ])
+    });
+    let table = Table::new(
+        passports,
+        [
+            Constraint::Length(6),
+            Constraint::Length(11),
+            Constraint::Length(6),
+        ],
+    )
+    .header(header(&["#", "Lines", "Result"]))
+    .block(Block::bordered().title(title))
+    .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    let mut state = TableState::new().with_selected(Some(selected));
+    frame.render_stateful_widget(table, list, &mut state);
+
+    let Some(passport) = rows.get(selected) else {
+        frame.render_widget(Block::bordered(), details);
+        return;
+    };
+    let mut fields = passport
+        .field_statuses()
+        .into_iter()
+        .map(|(key, value, problem)| {
+            let status = match problem {
+                Some(problem) => Line::styled(problem.to_string(), Style::new().fg(Color::Red)),
+                None => Line::styled("ok", Style::new().fg(Color::Green)),
+            };
+            Row::new(vec![
+                Line::from(key.to_string()),
+                Line::from(value.unwrap_or_default().to_string()),
+                status,
+            ])
+        })
+        .collect::<Vec<Row>>();
+    for error in passport.token_errors.iter() {
+        fields.push(Row::new(vec![
+            Line::from(format!("{}:{}", error.line, error.column)),
+            Line::from(""),
+            Line::styled(error.message.clone(), Style::new().fg(Color::Red)),
+        ]));
+    }
+    let title = format!(
+        " Passport {} (lines {}-{}) ",
+        passport.passport, passport.first_line, passport.last_line
+    );
+    let table = Table::new(
+        fields,
+        [
+            Constraint::Length(6),
+            Constraint::Length(14),
+            Constraint::Min(10),
+        ],
+    )
+    .header(header(&["Field", "Value", "Status"]))
+    .block(Block::bordered().title(title));
+    frame.render_widget(table, details);
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::{format_age, App, Done, Job, LastRun, LastRuns, View};
+    use crate::inspect::{Inspection, Slope};
+    use crate::registry::Registry;
+    use crate::verify::Outcome;
+    use ratatui::backend::TestBackend;
+    use ratatui::crossterm::event::KeyCode;
+    use ratatui::Terminal;
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    fn runs_file(name: &str) -> PathBuf {
+        let file_name =
+            std::env::temp_dir().join(format!("aoc-tui-{}-{}.toml", name, std::process::id()));
+        let _ = std::fs::remove_file(&file_name);
+        file_name
+    }
+
+    fn app(name: &str) -> App {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+        let registry = Registry::load_from(root).unwrap();
+        App::new(registry.solvers, LastRuns::default(), runs_file(name))
+    }
+
+    /// Text of the screen, one string per line.
+    fn render(app: &App, width: u16, height: u16, now: u64) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal.draw(|frame| app.draw(frame, now)).unwrap();
+        let buffer = terminal.backend().buffer().clone();
+        (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| buffer[(x, y)].symbol().to_string())
+                    .collect::<String>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn last_runs() {
+        let file_name = runs_file("last-runs");
+        let mut runs = LastRuns::default();
+        let outcome = Outcome::Pass(String::from("458"));
+        runs.insert(LastRun::new(
+            2,
+            1,
+            &outcome,
+            Some(Duration::from_micros(5)),
+            10,
+        ));
+        runs.insert(LastRun::new(
+            1,
+            2,
+            &Outcome::Error(String::from("x")),
+            None,
+            20,
+        ));
+        runs.insert(LastRun::new(2, 1, &outcome, None, 30));
+        runs.save(&file_name).unwrap();
+
+        let runs = LastRuns::load(&file_name).unwrap();
+        assert_eq!(runs.runs.len(), 2);
+        assert_eq!(runs.runs[0].status, "ERROR");
+        assert_eq!(runs.get(2, 1).unwrap().time, 30);
+        assert_eq!(runs.get(2, 1).unwrap().answer, "458");
+        assert_eq!(runs.get(2, 2), None);
+        std::fs::remove_file(&file_name).unwrap();
+
+        assert!(LastRuns::load(&file_name).unwrap().runs.is_empty());
+    }
+
+    #[test]
+    fn ages() {
+        assert_eq!(format_age(5), "just now");
+        assert_eq!(format_age(150), "2 min ago");
+        assert_eq!(format_age(7_200), "2 h ago");
+        assert_eq!(format_age(200_000), "2 d ago");
+    }
+
+    #[test]
+    fn keys() {
+        let mut app = app("keys");
+        assert!(app.on_key(KeyCode::Up).is_empty());
+        app.on_key(KeyCode::Down);
+        let jobs = app.on_key(KeyCode::Enter);
+        assert_eq!(jobs, vec![Job::Run(app.solvers[1].clone())]);
+        // Queued part isn't queued again.
+        assert_eq!(app.on_key(KeyCode::Char('a')).len(), app.solvers.len() - 1);
+
+        assert!(app.on_key(KeyCode::Char('i')).is_empty());
+        assert_eq!(app.message, "Day 1 doesn't have an inspector");
+        for _ in 0..4 {
+            app.on_key(KeyCode::Down);
+        }
+        assert_eq!(app.on_key(KeyCode::Char('i')), vec![Job::Inspect(3)]);
+
+        app.on_done(Done::Run {
+            day: 1,
+            part: 2,
+            outcome: Outcome::Pass(String::from("178724430")),
+            duration: Some(Duration::from_millis(3)),
+        });
+        assert!(!app.pending.contains(&(1, 2)));
+        let saved = LastRuns::load(&app.runs_file).unwrap();
+        assert_eq!(saved.get(1, 2).unwrap().answer, "178724430");
+        std::fs::remove_file(&app.runs_file).unwrap();
+
+        let slope = |right| Slope {
+            right,
+            down: 1,
+            trees: 1,
+            path: vec![(1, 3)],
+        };
+        app.on_done(Done::Inspect {
+            day: 3,
+            inspection: Ok(Inspection::Map {
+                lines: vec![String::from("..#"), String::from("#.#")],
+                slopes: vec![slope(1), slope(3)],
+            }),
+        });
+        app.on_key(KeyCode::Left);
+        app.on_key(KeyCode::PageDown);
+        assert!(matches!(
+            app.view,
+            View::Inspector {
+                selected: 1,
+                slope: 1,
+                ..
+            }
+        ));
+        app.on_key(KeyCode::Esc);
+        assert_eq!(app.view, View::Days);
+        app.on_key(KeyCode::Char('q'));
+        assert!(app.quit);
+    }
+
+    #[test]
+    fn dashboard() {
+        let mut app = app("dashboard");
+        let pass = Outcome::Pass(String::from("169"));
+        app.runs.insert(LastRun::new(
+            3,
+            1,
+            &pass,
+            Some(Duration::from_micros(1500)),
+            1_000,
+        ));
+        app.pending = vec![(4, 2)];
+
+        let screen = render(&app, 90, 14, 1_200);
+        let row = |day_part: &str| {
+            screen
+                .iter()
+                .find(|line| line.contains(day_part))
+                .unwrap()
+                .clone()
+        };
+        let day_3 = row("day-03/toboggan-trajectory");
+        assert!(day_3.contains("PASS"), "{}", day_3);
+        assert!(day_3.contains("169"));
+        assert!(day_3.contains("1.5 ms"));
+        assert!(day_3.contains("3 min ago"));
+        assert!(row("day-04/part-two").contains("RUNNING"));
+        assert!(screen[13].contains("i inspect"));
+    }
+
+    #[test]
+    fn map_inspector() {
+        let mut app = app("map-inspector");
+        app.view = View::Inspector {
+            day: 3,
+            inspection: Inspection::Map {
+                lines: vec![
+                    String::from("..##......."),
+                    String::from("#...#...#.."),
+                    String::from(".#....#..#."),
+                ],
+                slopes: vec![Slope {
+                    right: 3,
+                    down: 1,
+                    trees: 1,
+                    path: vec![(1, 3), (2, 6)],
+                }],
+            },
+            selected: 0,
+            slope: 0,
+        };
+
+        let screen = render(&app, 40, 8, 0);
+        assert!(screen[0].contains("slope right 3, down 1, 1 trees"));
+        assert!(screen[1].contains("..##......."));
+        assert!(screen[2].contains("#..O#...#.."));
+        assert!(screen[3].contains(".#....X..#."));
+    }
+
+    #[test]
+    fn non_ascii_map() {
+        let mut app = app("non-ascii-map");
+        app.view = View::Inspector {
+            day: 3,
+            inspection: Inspection::Map {
+                lines: vec![
+                    String::from("··#·"),
+                    String::from("·#·#"),
+                    String::from("····"),
+                ],
+                slopes: vec![Slope {
+                    right: 1,
+                    down: 1,
+                    trees: 1,
+                    path: vec![(1, 1), (2, 2), (3, 3)],
+                }],
+            },
+            selected: 0,
+            slope: 0,
+        };
+
+        let screen = render(&app, 40, 8, 0);
+        assert!(screen[2].contains("·X·#"));
+        assert!(screen[3].contains("··O·"));
+    }
+}
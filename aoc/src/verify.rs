@@ -0,0 +1,188 @@
+use crate::answers::{extract_answer, input_hash, Answers, ANSWERS_FILE};
+use crate::inputs::Inputs;
+use crate::registry::{Registry, Solver};
+use crate::runner;
+use aoc_common::Error;
+use std::fmt;
+use std::path::Path;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub day: Option<u8>,
+    /// Save answers of solvers which don't have a known answer yet.
+    pub record: bool,
+}
+
+/// Result of a solver compared with the known answer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Pass(String),
+    Fail {
+        expected: String,
+        actual: String,
+    },
+    /// No answer is known for the input.
+    Unknown(String),
+    /// Solver can't be built or run, or it didn't print any answer.
+    Error(String),
+}
+
+impl Outcome {
+    /// Short status, e.g. `PASS`.
+    pub fn status(&self) -> &'static str {
+        match self {
+            Outcome::Pass(_) => "PASS",
+            Outcome::Fail { .. } => "FAIL",
+            Outcome::Unknown(_) => "UNKNOWN",
+            Outcome::Error(_) => "ERROR",
+        }
+    }
+
+    /// Answer of the solver, or what went wrong.
+    pub fn details(&self) -> String {
+        match self {
+            Outcome::Pass(answer) | Outcome::Unknown(answer) => answer.clone(),
+            Outcome::Fail { expected, actual } => format!("expected {}, got {}", expected, actual),
+            Outcome::Error(message) => message.clone(),
+        }
+    }
+
+    pub fn compare(expected: Option<&str>, actual: &str) -> Outcome {
+        match expected {
+            Some(expected) if expected == actual => Outcome::Pass(actual.to_string()),
+            Some(expected) => Outcome::Fail {
+                expected: expected.to_string(),
+                actual: actual.to_string(),
+            },
+            None => Outcome::Unknown(actual.to_string()),
+        }
+    }
+}
+
+/// Runs every selected solver on its input and compares its answer with the
+/// known one. Wrong answers and broken solvers are reported as an error.
+pub fn verify(registry: &Registry, inputs: &Inputs, options: &Options) -> Result<(), Error> {
+    let answers_file = registry.root.join(ANSWERS_FILE);
+    let mut answers = Answers::load(&answers_file)?;
+    let mut outcomes = Vec::new();
+
+    for solver in registry.select(options.day)? {
+        let outcome = match solve(registry, inputs, solver) {
+            Ok((input_hash, answer)) => {
+                let outcome = Outcome::compare(answers.get(solver, &input_hash), &answer);
+                if options.record && matches!(outcome, Outcome::Unknown(_)) {
+                    answers.insert(solver, &input_hash, &answer);
+                }
+                outcome
+            }
+            Err(e) => Outcome::Error(e.to_string()),
+        };
+        println!("{:<14} {}", solver.name(), outcome);
+        outcomes.push(outcome);
+    }
+
+    let count = |f: fn(&Outcome) -> bool| outcomes.iter().filter(|outcome| f(outcome)).count();
+    let passed = count(|outcome| matches!(outcome, Outcome::Pass(_)));
+    let unknown = count(|outcome| matches!(outcome, Outcome::Unknown(_)));
+    let failed = outcomes.len() - passed - unknown;
+    println!(
+        "Passed: {}, failed: {}, unknown: {}",
+        passed, failed, unknown
+    );
+
+    if options.record && unknown > 0 {
+        answers.save(&answers_file)?;
+        println!("{} answers saved to {}", unknown, answers_file.display());
+    }
+    if failed > 0 {
+        return Err(Error::Check(format!("{} solvers failed", failed)));
+    }
+    Ok(())
+}
+
+/// Runs the built solver and compares its answer with the known one. Also
+/// returns the duration of the run, if it succeeded.
+pub fn outcome(
+    registry: &Registry,
+    inputs: &Inputs,
+    solver: &Solver,
+    executable: &Path,
+    answers: &Answers,
+) -> (Outcome, Option<Duration>) {
+    match solve_with(registry, inputs, solver, executable) {
+        Ok((input_hash, answer, duration)) => (
+            Outcome::compare(answers.get(solver, &input_hash), &answer),
+            Some(duration),
+        ),
+        Err(e) => (Outcome::Error(e.to_string()), None),
+    }
+}
+
+/// Hash of the input of the solver and the answer it printed.
+pub fn solve(
+    registry: &Registry,
+    inputs: &Inputs,
+    solver: &Solver,
+) -> Result<(String, String), Error> {
+    let executable = runner::build(&registry.root, solver)?;
+    solve_with(registry, inputs, solver, &executable).map(|(hash, answer, _)| (hash, answer))
+}
+
+/// Same as `solve` with an already built solver, also with the duration of
+/// its run.
+pub fn solve_with(
+    registry: &Registry,
+    inputs: &Inputs,
+    solver: &Solver,
+    executable: &Path,
+) -> Result<(String, String, Duration), Error> {
+    let input = inputs.input_of(&registry.root, solver);
+    let raw_input = std::fs::read(&input)
+        .map_err(|e| Error::io(format!("unable to read {}", input.display()), e))?;
+    let run = runner::run(executable, solver, &input, false)?;
+    if !run.success() {
+        return Err(Error::Check(format!(
+            "solver failed with exit code {:?}\n{}",
+            run.exit_code,
+            run.stderr.trim_end()
+        )));
+    }
+    let answer = extract_answer(&run.stdout)
+        .ok_or_else(|| Error::Check(String::from("solver didn't print any answer")))?;
+    Ok((input_hash(&raw_input), answer.to_string(), run.duration))
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:<8} {}", self.status(), self.details())
+    }
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::Outcome;
+
+    #[test]
+    fn outcomes() {
+        assert_eq!(
+            Outcome::compare(Some("458"), "458"),
+            Outcome::Pass(String::from("458"))
+        );
+        assert_eq!(
+            Outcome::compare(Some("458"), "457"),
+            Outcome::Fail {
+                expected: String::from("458"),
+                actual: String::from("457")
+            }
+        );
+        assert_eq!(
+            Outcome::compare(None, "458"),
+            Outcome::Unknown(String::from("458"))
+        );
+        assert_eq!(
+            Outcome::compare(Some("458"), "457").to_string(),
+            "FAIL     expected 458, got 457"
+        );
+    }
+}
@@ -0,0 +1,417 @@
+use crate::answers::{Answers, ANSWERS_FILE};
+use crate::bench::format_duration;
+use crate::examples::{self, EXAMPLES_DIR};
+use crate::inputs::Inputs;
+use crate::registry::{Registry, Solver};
+use crate::runner;
+use crate::verify::{self, Outcome};
+use aoc_common::Error;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// Time between two checks of the watched files.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// Files must stay unchanged this long before the solvers run again, so a
+/// burst of edits, e.g. saving of several files, runs them only once.
+pub const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Modification time and size of every watched file.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Snapshot(BTreeMap<PathBuf, (Option<SystemTime>, u64)>);
+
+/// Diff of the output of a failed example, or why it couldn't run.
+pub type ExampleResult = Result<Option<String>, String>;
+
+/// Result of a single run of all solvers of the day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub day: u8,
+    /// Number of the run, starting at 1.
+    pub run: usize,
+    /// Files changed since the previous run.
+    pub changed: Vec<PathBuf>,
+    /// Name of every example and its diff, if it failed.
+    pub examples: Result<Vec<(String, ExampleResult)>, String>,
+    /// Name of every part, its answer and duration of its run.
+    pub parts: Vec<(String, Outcome, Option<Duration>)>,
+}
+
+impl Snapshot {
+    /// Takes the snapshot of the files, and of all files in the directories.
+    /// Missing files are skipped.
+    pub fn take(paths: &[PathBuf]) -> Snapshot {
+        let mut snapshot = Snapshot::default();
+        for path in paths {
+            snapshot.add(path);
+        }
+        snapshot
+    }
+
+    fn add(&mut self, path: &Path) {
+        let Ok(metadata) = fs::metadata(path) else {
+            return;
+        };
+        if metadata.is_dir() {
+            // Build artifacts change on every run.
+            if path.file_name().is_some_and(|name| name == "target") {
+                return;
+            }
+            if let Ok(entries) = fs::read_dir(path) {
+                for entry in entries.flatten() {
+                    self.add(&entry.path());
+                }
+            }
+        } else {
+            self.0.insert(
+                path.to_path_buf(),
+                (metadata.modified().ok(), metadata.len()),
+            );
+        }
+    }
+
+    /// Files created, changed or removed since the earlier snapshot.
+    pub fn changes(&self, earlier: &Snapshot) -> Vec<PathBuf> {
+        let mut changes = self
+            .0
+            .iter()
+            .filter(|(path, state)| earlier.0.get(*path) != Some(state))
+            .map(|(path, _)| path.clone())
+            .chain(
+                earlier
+                    .0
+                    .keys()
+                    .filter(|path| !self.0.contains_key(*path))
+                    .cloned(),
+            )
+            .collect::<Vec<PathBuf>>();
+        changes.sort();
+        changes
+    }
+}
+
+/// Sources and inputs of the solvers of the day and its examples.
+pub fn watched_paths(registry: &Registry, inputs: &Inputs, solvers: &[&Solver]) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    for solver in solvers {
+        let dir = solver.dir(&registry.root);
+        paths.push(dir.join("Cargo.toml"));
+        paths.push(dir.join("src"));
+        paths.push(inputs.input_of(&registry.root, solver));
+    }
+    if let Some(solver) = solvers.first() {
+        paths.push(
+            registry
+                .root
+                .join(EXAMPLES_DIR)
+                .join(format!("day{:02}", solver.day)),
+        );
+    }
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+/// Waits until some of the files change and then stay unchanged for
+/// `debounce`. Returns all files changed in the meantime; `snapshot` is
+/// updated to the last state of the files.
+pub fn wait_for_changes(
+    paths: &[PathBuf],
+    snapshot: &mut Snapshot,
+    poll_interval: Duration,
+    debounce: Duration,
+) -> Vec<PathBuf> {
+    let start = snapshot.clone();
+    loop {
+        thread::sleep(poll_interval);
+        let current = Snapshot::take(paths);
+        if current.changes(snapshot).is_empty() {
+            continue;
+        }
+        *snapshot = current;
+
+        // Every further change restarts the quiet period.
+        let mut quiet = Duration::ZERO;
+        while quiet < debounce {
+            thread::sleep(poll_interval);
+            let current = Snapshot::take(paths);
+            if current.changes(snapshot).is_empty() {
+                quiet += poll_interval;
+            } else {
+                *snapshot = current;
+                quiet = Duration::ZERO;
+            }
+        }
+
+        let changes = snapshot.changes(&start);
+        // Files may be changed and changed back, which isn't a change.
+        if !changes.is_empty() {
+            return changes;
+        }
+    }
+}
+
+/// Runs the examples and the input of the day every time its sources or
+/// inputs change. Runs until the process is interrupted.
+pub fn watch(registry: &Registry, inputs: &Inputs, day: u8) -> Result<(), Error> {
+    let solvers = registry.select(Some(day))?;
+    let paths = watched_paths(registry, inputs, &solvers);
+    let mut snapshot = Snapshot::take(&paths);
+    let mut changed = Vec::new();
+    for run in 1.. {
+        println!("Running day {}...", day);
+        let report = run_day(registry, inputs, &solvers, run, changed);
+        if std::io::stdout().is_terminal() {
+            // Clears the screen and moves the cursor to the top.
+            print!("\x1b[2J\x1b[H");
+        }
+        print!("{}", render(&report, &registry.root));
+
+        changed = wait_for_changes(&paths, &mut snapshot, POLL_INTERVAL, DEBOUNCE);
+    }
+    Ok(())
+}
+
+fn run_day(
+    registry: &Registry,
+    inputs: &Inputs,
+    solvers: &[&Solver],
+    run: usize,
+    changed: Vec<PathBuf>,
+) -> Report {
+    let executables = solvers
+        .iter()
+        .map(|solver| runner::build(&registry.root, solver).map_err(|e| e.to_string()))
+        .collect::<Vec<Result<PathBuf, String>>>();
+    let executable = |part: u8| {
+        solvers
+            .iter()
+            .position(|solver| solver.part == part)
+            .map(|i| (solvers[i], &executables[i]))
+    };
+
+    let day = solvers[0].day;
+    let examples = examples::discover(&registry.root, Some(day))
+        .map_err(|e| e.to_string())
+        .map(|examples| {
+            examples
+                .iter()
+                .map(|example| {
+                    let result = match executable(example.part) {
+                        Some((solver, Ok(executable))) => {
+                            examples::check(executable, solver, example).map_err(|e| e.to_string())
+                        }
+                        Some((_, Err(_))) => Err(String::from("build failed")),
+                        None => Err(format!("part {} is not registered", example.part)),
+                    };
+                    (example.name(), result)
+                })
+                .collect()
+        });
+
+    let answers = Answers::load(&registry.root.join(ANSWERS_FILE));
+    let parts = solvers
+        .iter()
+        .zip(executables.iter())
+        .map(|(solver, executable)| {
+            let (outcome, duration) = match (executable, &answers) {
+                (Ok(executable), Ok(answers)) => {
+                    verify::outcome(registry, inputs, solver, executable, answers)
+                }
+                (Err(message), _) => (Outcome::Error(message.clone()), None),
+                (_, Err(e)) => (Outcome::Error(e.to_string()), None),
+            };
+            (solver.name(), outcome, duration)
+        })
+        .collect();
+
+    Report {
+        day,
+        run,
+        changed,
+        examples,
+        parts,
+    }
+}
+
+/// Compact view of the report: changed files, failed examples only, and the
+/// answer of every part.
+pub fn render(report: &Report, root: &Path) -> String {
+    let mut view = format!("aoc watch: day {}, run {}", report.day, report.run);
+    if !report.changed.is_empty() {
+        let changed = report
+            .changed
+            .iter()
+            .map(|path| {
+                path.strip_prefix(root)
+                    .unwrap_or(path)
+                    .display()
+                    .to_string()
+            })
+            .collect::<Vec<String>>();
+        view.push_str(&format!(", changed {}", changed.join(", ")));
+    }
+    view.push('\n');
+
+    match &report.examples {
+        Ok(examples) => {
+            let failed = examples
+                .iter()
+                .filter(|(_, result)| !matches!(result, Ok(None)))
+                .collect::<Vec<_>>();
+            view.push_str(&format!(
+                "examples: {} passed, {} failed\n",
+                examples.len() - failed.len(),
+                failed.len()
+            ));
+            for (name, result) in failed {
+                let (status, details) = match result {
+                    Ok(Some(diff)) => ("FAIL ", diff.as_str()),
+                    Err(message) => ("ERROR", message.as_str()),
+                    Ok(None) => continue,
+                };
+                view.push_str(&format!("  {} {}\n", status, name));
+                for line in details.trim_end().lines() {
+                    view.push_str(&format!("    {}\n", line));
+                }
+            }
+        }
+        Err(message) => view.push_str(&format!("examples: {}\n", message)),
+    }
+
+    for (name, outcome, duration) in report.parts.iter() {
+        let duration = duration.map(format_duration).unwrap_or_default();
+        // Errors, e.g. compiler errors, take several lines.
+        let outcome = outcome.to_string();
+        let mut lines = outcome.trim_end().lines();
+        view.push_str(&format!(
+            "{:<14} {:>10}  {}\n",
+            name,
+            duration,
+            lines.next().unwrap_or_default()
+        ));
+        for line in lines {
+            view.push_str(&format!("    {}\n", line));
+        }
+    }
+    view.push_str("Waiting for changes, press Ctrl-C to stop\n");
+    view
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::{render, wait_for_changes, Report, Snapshot};
+    use crate::verify::Outcome;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-watch-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn changes() {
+        let dir = temp_dir("changes");
+        fs::write(dir.join("src/main.rs"), "fn main() {}").unwrap();
+        fs::write(dir.join("input.dat"), "1\n").unwrap();
+        fs::create_dir_all(dir.join("src/target")).unwrap();
+        let paths = vec![dir.join("src"), dir.join("input.dat"), dir.join("missing")];
+        let before = Snapshot::take(&paths);
+        assert!(Snapshot::take(&paths).changes(&before).is_empty());
+
+        fs::write(dir.join("input.dat"), "1\n2\n").unwrap();
+        fs::write(dir.join("src/lib.rs"), "").unwrap();
+        fs::write(dir.join("src/target/ignored"), "").unwrap();
+        fs::remove_file(dir.join("src/main.rs")).unwrap();
+        assert_eq!(
+            Snapshot::take(&paths).changes(&before),
+            vec![
+                dir.join("input.dat"),
+                dir.join("src/lib.rs"),
+                dir.join("src/main.rs")
+            ]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn debounce() {
+        let dir = temp_dir("debounce");
+        let paths = vec![dir.join("src")];
+        let mut snapshot = Snapshot::take(&paths);
+
+        let src = dir.join("src");
+        let writer = thread::spawn(move || {
+            for i in 0..4 {
+                thread::sleep(Duration::from_millis(40));
+                fs::write(src.join(format!("{}.rs", i % 2)), "x".repeat(i + 1)).unwrap();
+            }
+        });
+        let start = Instant::now();
+        let changes = wait_for_changes(
+            &paths,
+            &mut snapshot,
+            Duration::from_millis(10),
+            Duration::from_millis(100),
+        );
+        writer.join().unwrap();
+
+        // All writes of the burst are reported together, after the last one.
+        assert!(start.elapsed() >= Duration::from_millis(250));
+        assert_eq!(changes, vec![dir.join("src/0.rs"), dir.join("src/1.rs")]);
+        assert!(Snapshot::take(&paths).changes(&snapshot).is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn compact_view() {
+        let root = Path::new("/repo");
+        let report = Report {
+            day: 3,
+            run: 2,
+            changed: vec![root.join("day-03/part-two/src/lib.rs")],
+            examples: Ok(vec![
+                (String::from("day03/map part 1"), Ok(None)),
+                (
+                    String::from("day03/map part 2"),
+                    Ok(Some(String::from("- 336\n+ 335\n"))),
+                ),
+            ]),
+            parts: vec![
+                (
+                    String::from("day 3 part 1"),
+                    Outcome::Pass(String::from("169")),
+                    Some(Duration::from_micros(1500)),
+                ),
+                (
+                    String::from("day 3 part 2"),
+                    Outcome::Error(String::from("build failed\nerror[E0308]")),
+                    None,
+                ),
+            ],
+        };
+
+        assert_eq!(
+            render(&report, root),
+            "aoc watch: day 3, run 2, changed day-03/part-two/src/lib.rs\n\
+             examples: 1 passed, 1 failed\n  \
+             FAIL  day03/map part 2\n    \
+             - 336\n    \
+             + 335\n\
+             day 3 part 1       1.5 ms  PASS     169\n\
+             day 3 part 2               ERROR    build failed\n    \
+             error[E0308]\n\
+             Waiting for changes, press Ctrl-C to stop\n"
+        );
+    }
+}
@@ -0,0 +1,44 @@
+use aoc_common::{phase, Args, Error};
+use std::process::ExitCode;
+
+const USAGE: &str = "Program must be executed with one argument: [input]";
+
+/// Puzzle input after parsing.
+type Input = Vec<String>;
+
+fn main() -> ExitCode {
+    aoc_common::run(solve)
+}
+
+fn solve() -> Result<(), Error> {
+    let args = Args::from_env(USAGE, 0..=0)?;
+    let input = phase("parse", || parse(args.input.lines()?))?;
+    let answer = phase("solve", || part_two(&input))?;
+    println!("Part 2: {}", answer);
+    Ok(())
+}
+
+fn parse(lines: Vec<String>) -> Result<Input, Error> {
+    Ok(lines)
+}
+
+fn part_two(_input: &Input) -> Result<u64, Error> {
+    Err(Error::NoSolution(String::from("part two isn't solved yet")))
+}
+
+#[cfg(test)]
+mod example_data {
+    // Example of the puzzle, also run with its expected outputs by
+    // `aoc examples`.
+    const EXAMPLE: &str = include_str!("../../../examples/day{{padded_day}}/example.txt");
+
+    fn input() -> super::Input {
+        super::parse(EXAMPLE.lines().map(String::from).collect()).unwrap()
+    }
+
+    #[test]
+    #[ignore = "example of day {{day}} isn't filled in yet"]
+    fn part_two() {
+        assert_eq!(super::part_two(&input()).unwrap(), 0);
+    }
+}
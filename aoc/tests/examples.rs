@@ -0,0 +1,21 @@
+use std::path::Path;
+use std::process::Command;
+
+/// Runs every example in `examples` of the repository with the `aoc`
+/// runner, which builds all solvers first.
+#[test]
+fn repository_examples() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc"))
+        .arg("examples")
+        .current_dir(root)
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -1,37 +1,23 @@
+use aoc_common::{phase, Args, Error};
 use combinations::Combinations;
-use std::env;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
+use std::process::ExitCode;
 
-fn main() {
-    let args = env::args().collect::<Vec<String>>();
-    if args.len() != 2 {
-        eprintln!("Program must be executed with one argument: [file_name]");
-        return;
-    }
+const USAGE: &str = "Program must be executed with one argument: [input]";
 
-    // First argument is name of binary file.
-    // Usefully is second argument which is `file name` with data.
-    let file_name = match args.get(1) {
-        Some(name) => name,
-        None => {
-            eprintln!("unable to get file name");
-            return;
-        }
-    };
+fn main() -> ExitCode {
+    aoc_common::run(solve)
+}
 
-    let path = Path::new(file_name);
-    let data = match parse_file_to_vector(path) {
-        Ok(data) => data,
-        Err(e) => {
-            eprintln!("unable to parse data from file, {}", e);
-            return;
-        }
-    };
+fn solve() -> Result<(), Error> {
+    let args = Args::from_env(USAGE, 0..=0)?;
+    let data = phase("parse", || args.input.integers::<usize>())?;
 
-    let output = product_data_that_sum_to_2020(data);
+    let output = phase("solve", || product_data_that_sum_to_2020(data));
+    if output.is_empty() {
+        return Err(Error::NoSolution(String::from("no entries sum to 2020")));
+    }
     println!("{:?}", output);
+    Ok(())
 }
 
 fn product_data_that_sum_to_2020(data: Vec<usize>) -> Vec<usize> {
@@ -43,21 +29,17 @@ fn product_data_that_sum_to_2020(data: Vec<usize>) -> Vec<usize> {
         .collect::<Vec<usize>>()
 }
 
-fn parse_file_to_vector(file_name: &Path) -> std::io::Result<Vec<usize>> {
-    let file = File::open(file_name)?;
-    let reader = BufReader::new(file);
-    Ok(reader
-        .lines()
-        .map(|line| line.ok().and_then(|s| s.parse::<usize>().ok()).unwrap_or(0))
-        .collect::<Vec<usize>>())
-}
-
 #[cfg(test)]
 mod example_data {
+    const EXPENSE_REPORT: &str = include_str!("../../../examples/day01/expense-report.txt");
+
     #[test]
     fn example_data() {
         // 1721 + 299 = 2020
-        let input = vec![1721, 979, 366, 299, 675, 1456];
+        let input = EXPENSE_REPORT
+            .lines()
+            .map(|line| line.parse().unwrap())
+            .collect();
         let output = crate::product_data_that_sum_to_2020(input);
         assert_eq!(output[0], 514579);
     }
@@ -1,8 +1,9 @@
+use aoc_common::{phase, Args, Error};
+use serde_json::json;
 use std::convert::TryFrom;
-use std::env;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
+use std::process::ExitCode;
+
+const USAGE: &str = "Program must be executed with arguments: [input] [--report json]";
 
 // Example input data: "1-3 a: abcde"
 // `1-3 a` means that the password must contain `a` at least `1` time and at
@@ -14,40 +15,66 @@ struct PasswordPolicy {
     pub password: String,
 }
 
-fn main() {
-    let args = env::args().collect::<Vec<String>>();
-    if args.len() != 2 {
-        eprintln!("Program must be executed with one argument: [file_name]");
-        return;
-    }
-
-    // First argument is name of binary file.
-    // Usefully is second argument which is `file name` with data.
-    let file_name = match args.get(1) {
-        Some(name) => name,
-        None => {
-            eprintln!("unable to get file name");
-            return;
-        }
-    };
+fn main() -> ExitCode {
+    aoc_common::run(solve)
+}
 
-    let path = Path::new(file_name);
-    let lines = match parse_file_to_vector(path) {
-        Ok(lines) => lines,
-        Err(e) => {
-            eprintln!("unable to parse data from file, {}", e);
-            return;
-        }
+fn solve() -> Result<(), Error> {
+    let args = Args::from_env(USAGE, 0..=2)?;
+    // `--report json` prints every policy and whether its password is valid,
+    // instead of the number of valid passwords.
+    let report = match args.rest.as_slice() {
+        [] => false,
+        [name, format] if name == "--report" && format == "json" => true,
+        _ => return Err(Error::Usage(USAGE.to_string())),
     };
-    let correct_passwords =
-        lines
-            .into_iter()
-            .flat_map(PasswordPolicy::try_from)
-            .fold(
-                0,
-                |acc, password| if password.is_valid() { acc + 1 } else { acc },
-            );
+    let policies = phase("parse", || parse_policies(args.input.lines()?))?;
+    if report {
+        println!("{}", json_report(&policies));
+        return Ok(());
+    }
+    let correct_passwords = phase("solve", || {
+        policies.into_iter().fold(
+            0,
+            |acc, password| if password.is_valid() { acc + 1 } else { acc },
+        )
+    });
     println!("Correct passwords: {}", correct_passwords);
+    Ok(())
+}
+
+/// Every line of the input must be a valid password policy.
+fn parse_policies(lines: Vec<String>) -> Result<Vec<PasswordPolicy>, Error> {
+    let mut policies = Vec::with_capacity(lines.len());
+    for (i, line) in lines.into_iter().enumerate() {
+        let policy = PasswordPolicy::try_from(line)
+            .map_err(|e| Error::Parse(format!("line {}: {}", i + 1, e)))?;
+        policy
+            .check()
+            .map_err(|e| Error::Validation(format!("line {}: {}", i + 1, e)))?;
+        policies.push(policy);
+    }
+    Ok(policies)
+}
+
+/// Report as a JSON array, with one object per line of the input.
+fn json_report(policies: &[PasswordPolicy]) -> String {
+    let policies = policies
+        .iter()
+        .enumerate()
+        .map(|(i, policy)| {
+            json!({
+                "line": i + 1,
+                "policy": format!(
+                    "{}-{} {}",
+                    policy.min_repeat, policy.max_repeat, policy.letter
+                ),
+                "password": policy.password,
+                "valid": policy.is_valid(),
+            })
+        })
+        .collect::<Vec<serde_json::Value>>();
+    serde_json::Value::Array(policies).to_string()
 }
 
 impl TryFrom<String> for PasswordPolicy {
@@ -64,12 +91,11 @@ impl TryFrom<String> for PasswordPolicy {
 
         // This `unwrap()` is safe because we've checked the size of the
         // vector before.
-        let min_max = split_line.get(0).unwrap();
+        let min_max = split_line.first().unwrap();
         let first_letter = split_line
             .get(1)
             .unwrap()
             .chars()
-            .into_iter()
             .next()
             .ok_or_else(|| String::from("problem with take letter"))?;
         let password = split_line.get(2).unwrap().to_string();
@@ -80,7 +106,7 @@ impl TryFrom<String> for PasswordPolicy {
             return Err(error_message);
         }
         let min_repeat = split_min_max
-            .get(0)
+            .first()
             .unwrap()
             .parse::<u8>()
             .map_err(|e| format!("unable to parse min repeat, {}", e))?;
@@ -99,14 +125,29 @@ impl TryFrom<String> for PasswordPolicy {
 }
 
 impl PasswordPolicy {
+    /// Checks rules of the policy which don't depend on the password.
+    fn check(&self) -> Result<(), String> {
+        if self.min_repeat > self.max_repeat {
+            return Err(format!(
+                "min repeat {} is greater than max repeat {}",
+                self.min_repeat, self.max_repeat
+            ));
+        }
+        Ok(())
+    }
+
     fn is_valid(&self) -> bool {
-        let letter_counter = self.password.chars().into_iter().fold(0, |acc, letter| {
-            if letter == self.letter {
-                acc + 1
-            } else {
-                acc
-            }
-        });
+        let letter_counter =
+            self.password.chars().fold(
+                0,
+                |acc, letter| {
+                    if letter == self.letter {
+                        acc + 1
+                    } else {
+                        acc
+                    }
+                },
+            );
         letter_counter >= self.min_repeat && letter_counter <= self.max_repeat
 
         // Second version
@@ -122,34 +163,33 @@ impl PasswordPolicy {
     }
 }
 
-fn parse_file_to_vector(file_name: &Path) -> std::io::Result<Vec<String>> {
-    let file = File::open(file_name)?;
-    let reader = BufReader::new(file);
-    Ok(reader
-        .lines()
-        .map(|line| line.unwrap_or_else(|_| "".to_owned()))
-        .collect::<Vec<String>>())
-}
-
 #[cfg(test)]
 mod example_data {
+    const POLICIES: &str = include_str!("../../../examples/day02/policies.txt");
+    const MALFORMED_POLICY: &str = include_str!("../../../examples/day02/malformed-policy.txt");
+    const REVERSED_RANGE: &str = include_str!("../../../examples/day02/reversed-range.txt");
+
+    fn lines(example: &str) -> Vec<String> {
+        example.lines().map(String::from).collect()
+    }
+
     #[test]
     fn example_data() {
-        let input = vec![
-            String::from("invalid-data 1"),   // invalid
-            String::from("invalid-data 1 2"), // invalid
-            String::from("1-3 a: abcde"),     // valid
-            String::from("1-3 b: cdefg"),     // invalid
-            String::from("2-9 c: ccccccccc"), // valid
-        ];
-
-        let valid_passwords = input
+        let valid_passwords = super::parse_policies(lines(POLICIES))
+            .unwrap()
             .into_iter()
-            .flat_map(super::PasswordPolicy::try_from)
-            .fold(
-                0,
-                |acc, password| if password.is_valid() { acc + 1 } else { acc },
-            );
+            .filter(|password| password.is_valid())
+            .count();
         assert_eq!(valid_passwords, 2);
     }
+
+    #[test]
+    fn invalid_data() {
+        let error = super::parse_policies(lines(MALFORMED_POLICY))
+            .err()
+            .unwrap();
+        assert_eq!(error.exit_code(), 4);
+        let error = super::parse_policies(lines(REVERSED_RANGE)).err().unwrap();
+        assert_eq!(error.exit_code(), 5);
+    }
 }
@@ -0,0 +1,53 @@
+//! Benchmarks based on the real puzzle input from `input.dat` of the day.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use part_two::{MovementScheme, TobogganTrajectory, Trajectory, TreeIndex};
+
+const INPUT: &str = include_str!("../../input.dat");
+
+const SLOPES: [(isize, usize); 5] = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+
+fn slope(right: isize, down: usize) -> Trajectory {
+    // This `unwrap()` is safe because every benchmarked slope goes down.
+    Trajectory::try_from(MovementScheme { right, down }).unwrap()
+}
+
+fn lines() -> impl Iterator<Item = String> {
+    INPUT.lines().map(String::from)
+}
+
+fn single_slope(c: &mut Criterion) {
+    let mut group = c.benchmark_group("single slope");
+    group.bench_function("line-by-line simulation", |b| {
+        b.iter(|| lines().calculate_encountered_trees(slope(3, 1)))
+    });
+    group.bench_function("tree index with preprocessing", |b| {
+        b.iter(|| lines().collect::<TreeIndex>().count_trees(slope(3, 1)))
+    });
+    group.finish();
+}
+
+fn multiple_slopes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("multiple slopes");
+    group.bench_function("line-by-line simulation", |b| {
+        b.iter(|| {
+            SLOPES
+                .into_iter()
+                .map(|(right, down)| lines().calculate_encountered_trees(slope(right, down)))
+                .product::<usize>()
+        })
+    });
+    group.bench_function("tree index with preprocessing", |b| {
+        b.iter(|| {
+            let tree_index = lines().collect::<TreeIndex>();
+            SLOPES
+                .into_iter()
+                .map(|(right, down)| tree_index.count_trees(slope(right, down)))
+                .product::<usize>()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, single_slope, multiple_slopes);
+criterion_main!(benches);
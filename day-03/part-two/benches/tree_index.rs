@@ -0,0 +1,109 @@
+//! Compares counting trees with line-by-line simulation and with `TreeIndex`.
+//!
+//! The default map has 10^4 lines and 10^4 columns. Its size can be changed
+//! with `TREE_INDEX_BENCH_LINES` and `TREE_INDEX_BENCH_COLUMNS`, e.g. for a
+//! map with 10^6 lines, which takes minutes and much more memory:
+//!
+//! ```text
+//! TREE_INDEX_BENCH_LINES=1000000 cargo bench --bench tree_index
+//! ```
+//!
+//! `tree index with preprocessing` also measures building of the index, so
+//! comparing it with `tree index` shows how many slopes are needed before the
+//! index pays off.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use part_two::{MovementScheme, TobogganTrajectory, Trajectory, TreeIndex};
+use std::env;
+
+const DEFAULT_LINES: usize = 10_000;
+const DEFAULT_COLUMNS: usize = 10_000;
+/// Number of distinct lines of the map. Longer maps repeat them, so even big
+/// maps are generated quickly.
+const DISTINCT_LINES: usize = 1_000;
+
+const SLOPES: [(isize, usize); 5] = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+
+/// Generates the same pseudo-random lines every time.
+fn generate_lines(lines: usize, columns: usize) -> Vec<String> {
+    // Xorshift generator, good enough to scatter trees on the map.
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    (0..lines)
+        .map(|_| {
+            (0..columns)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    if state.is_multiple_of(4) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>()
+        })
+        .collect()
+}
+
+/// Map with the given number of lines, which repeats the generated lines.
+fn map(distinct_lines: &[String], lines: usize) -> impl Iterator<Item = String> + '_ {
+    distinct_lines.iter().cycle().take(lines).cloned()
+}
+
+fn slope(right: isize, down: usize) -> Trajectory {
+    // This `unwrap()` is safe because every benchmarked slope goes down.
+    Trajectory::try_from(MovementScheme { right, down }).unwrap()
+}
+
+fn env_or(name: &str, default: usize) -> usize {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.parse::<usize>().ok())
+        .unwrap_or(default)
+}
+
+fn tree_index(c: &mut Criterion) {
+    let lines = env_or("TREE_INDEX_BENCH_LINES", DEFAULT_LINES);
+    let columns = env_or("TREE_INDEX_BENCH_COLUMNS", DEFAULT_COLUMNS);
+
+    // The map is generated outside of the measured code, so only counting
+    // of the trees and building of the index are measured.
+    let distinct_lines = generate_lines(lines.min(DISTINCT_LINES), columns);
+
+    let mut group = c.benchmark_group(format!("map {}x{}", lines, columns));
+    group.sample_size(10);
+    group.bench_function("line-by-line simulation", |b| {
+        b.iter(|| {
+            SLOPES
+                .into_iter()
+                .map(|(right, down)| {
+                    map(&distinct_lines, lines).calculate_encountered_trees(slope(right, down))
+                })
+                .product::<usize>()
+        })
+    });
+
+    let tree_index = map(&distinct_lines, lines).collect::<TreeIndex>();
+    group.bench_function("tree index", |b| {
+        b.iter(|| {
+            SLOPES
+                .into_iter()
+                .map(|(right, down)| tree_index.count_trees(slope(right, down)))
+                .product::<usize>()
+        })
+    });
+    group.bench_function("tree index with preprocessing", |b| {
+        b.iter(|| {
+            let tree_index = map(&distinct_lines, lines).collect::<TreeIndex>();
+            SLOPES
+                .into_iter()
+                .map(|(right, down)| tree_index.count_trees(slope(right, down)))
+                .product::<usize>()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, tree_index);
+criterion_main!(benches);
@@ -0,0 +1,386 @@
+use crate::{Trajectory, TreeIndex};
+use std::fmt;
+use std::str::FromStr;
+
+/// Describes what happens when the toboggan reaches the edge of the map.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BoundaryMode {
+    /// The map repeats to the left and to the right. The ride ends at the
+    /// bottom of the map. This is the mode from the original puzzle.
+    HorizontalWrap,
+    /// The map repeats in all directions. After passing the bottom of the map
+    /// the toboggan continues from the top, until it completes `laps` laps.
+    Torus { laps: usize },
+    /// The toboggan can't leave the map to the side, it slides along the
+    /// first or the last column instead.
+    Clamp,
+    /// The ride ends as soon as the toboggan leaves the map in any direction.
+    StopAtEdge,
+}
+
+/// Edge of the map where the toboggan left it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Edge {
+    Left,
+    Right,
+    Bottom,
+}
+
+/// Last field visited by the toboggan before it left the map.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exit {
+    /// Line of the map, numbered from 0.
+    pub line: usize,
+    /// Column of the map, numbered from 0.
+    pub column: usize,
+    pub edge: Edge,
+    /// Number of started laps, always `1` if the map doesn't wrap vertically.
+    /// It is `0` only for a torus with no laps, where the ride doesn't start.
+    pub laps: usize,
+}
+
+/// Result of a single ride down the map.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ride {
+    pub encountered_trees: usize,
+    pub exit: Exit,
+}
+
+impl FromStr for BoundaryMode {
+    type Err = String;
+
+    /// Parses the name of the mode: `wrap`, `torus:N` where `N` is the number
+    /// of laps, `clamp` or `stop`.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "wrap" => Ok(BoundaryMode::HorizontalWrap),
+            "clamp" => Ok(BoundaryMode::Clamp),
+            "stop" => Ok(BoundaryMode::StopAtEdge),
+            _ => match name.strip_prefix("torus:") {
+                Some(laps) => laps
+                    .parse::<usize>()
+                    .map(|laps| BoundaryMode::Torus { laps })
+                    .map_err(|e| format!("unable to parse laps of torus, {}", e)),
+                None => Err(format!(
+                    "unknown boundary mode {}, expected wrap, torus:N, clamp or stop",
+                    name
+                )),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Edge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Edge::Left => "left",
+            Edge::Right => "right",
+            Edge::Bottom => "bottom",
+        };
+        f.write_str(name)
+    }
+}
+
+impl TreeIndex {
+    pub fn ride<T: Into<Trajectory>>(&self, trajectory: T, boundary_mode: BoundaryMode) -> Ride {
+        self.ride_with(trajectory.into(), boundary_mode, |_, _| {})
+    }
+
+    /// Same as `ride`, but also returns line number and column of every field
+    /// where the toboggan stops, in the order of the ride. Both are numbered
+    /// from 0.
+    pub fn ride_path<T: Into<Trajectory>>(
+        &self,
+        trajectory: T,
+        boundary_mode: BoundaryMode,
+    ) -> (Ride, Vec<(usize, usize)>) {
+        let mut path = Vec::new();
+        let ride = self.ride_with(trajectory.into(), boundary_mode, |line, column| {
+            path.push((line, column))
+        });
+        (ride, path)
+    }
+
+    fn ride_with<F>(
+        &self,
+        trajectory: Trajectory,
+        boundary_mode: BoundaryMode,
+        mut visit: F,
+    ) -> Ride
+    where
+        F: FnMut(usize, usize),
+    {
+        let height = self.height();
+        let max_laps = match boundary_mode {
+            BoundaryMode::Torus { laps } => laps,
+            _ => 1,
+        };
+
+        let mut line = 0;
+        let mut column = 0;
+        let mut laps = 1;
+        let mut encountered_trees = 0;
+        let exit = |line, column, edge, laps| Exit {
+            line,
+            column,
+            edge,
+            laps,
+        };
+
+        if max_laps == 0 || height == 0 {
+            return Ride {
+                encountered_trees,
+                exit: exit(line, column, Edge::Bottom, max_laps.min(1)),
+            };
+        }
+
+        for movement in trajectory.0.iter().cycle() {
+            let mut next_line = line + movement.down;
+            if next_line >= height {
+                laps += next_line / height;
+                if laps > max_laps {
+                    return Ride {
+                        encountered_trees,
+                        exit: exit(line, column, Edge::Bottom, laps.min(max_laps)),
+                    };
+                }
+                next_line %= height;
+            }
+
+            // This `unwrap()` is safe because `next_line` is within the map.
+            let width = self.width(next_line).unwrap() as isize;
+            if width == 0 {
+                // Blank lines don't contain any field to visit.
+                line = next_line;
+                continue;
+            }
+            let next_column = column as isize + movement.right;
+            let next_column = match boundary_mode {
+                BoundaryMode::HorizontalWrap | BoundaryMode::Torus { .. } => {
+                    next_column.rem_euclid(width)
+                }
+                BoundaryMode::Clamp => next_column.clamp(0, width - 1),
+                BoundaryMode::StopAtEdge if next_column < 0 => {
+                    return Ride {
+                        encountered_trees,
+                        exit: exit(line, column, Edge::Left, laps),
+                    };
+                }
+                BoundaryMode::StopAtEdge if next_column >= width => {
+                    return Ride {
+                        encountered_trees,
+                        exit: exit(line, column, Edge::Right, laps),
+                    };
+                }
+                BoundaryMode::StopAtEdge => next_column,
+            };
+
+            line = next_line;
+            column = next_column as usize;
+            visit(line, column);
+            if self.is_tree(line, column) {
+                encountered_trees += 1;
+            }
+        }
+        unreachable!("trajectory always contains at least one move")
+    }
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::{BoundaryMode, Edge, Exit, Ride};
+    use crate::{slope, TreeIndex};
+
+    fn example_map() -> TreeIndex {
+        vec![
+            String::from("....."),
+            String::from("...#."),
+            String::from("#...."),
+            String::from("....#"),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn horizontal_wrap() {
+        let ride = example_map().ride(slope(3, 1), BoundaryMode::HorizontalWrap);
+
+        assert_eq!(
+            ride,
+            Ride {
+                encountered_trees: 2,
+                exit: Exit {
+                    line: 3,
+                    column: 4,
+                    edge: Edge::Bottom,
+                    laps: 1,
+                },
+            }
+        );
+        assert_eq!(
+            ride.encountered_trees,
+            example_map().count_trees(slope(3, 1))
+        );
+    }
+
+    #[test]
+    fn torus() {
+        let single_lap = example_map().ride(slope(3, 1), BoundaryMode::Torus { laps: 1 });
+        let horizontal_wrap = example_map().ride(slope(3, 1), BoundaryMode::HorizontalWrap);
+        assert_eq!(single_lap, horizontal_wrap);
+
+        // Second lap: (0, 2), (1, 0), (2, 3), (3, 1), third lap: (0, 4),
+        // (1, 2), (2, 0), (3, 3).
+        let ride = example_map().ride(slope(3, 1), BoundaryMode::Torus { laps: 3 });
+        assert_eq!(
+            ride,
+            Ride {
+                encountered_trees: 3,
+                exit: Exit {
+                    line: 3,
+                    column: 3,
+                    edge: Edge::Bottom,
+                    laps: 3,
+                },
+            }
+        );
+
+        let ride = example_map().ride(slope(3, 1), BoundaryMode::Torus { laps: 0 });
+        assert_eq!(ride.encountered_trees, 0);
+        assert_eq!(ride.exit.laps, 0);
+    }
+
+    #[test]
+    fn clamp() {
+        // Path: (1, 3), (2, 4), (3, 4).
+        let ride = example_map().ride(slope(3, 1), BoundaryMode::Clamp);
+        assert_eq!(
+            ride,
+            Ride {
+                encountered_trees: 2,
+                exit: Exit {
+                    line: 3,
+                    column: 4,
+                    edge: Edge::Bottom,
+                    laps: 1,
+                },
+            }
+        );
+
+        // Path: (1, 0), (2, 0), (3, 0).
+        let ride = example_map().ride(slope(-2, 1), BoundaryMode::Clamp);
+        assert_eq!(ride.encountered_trees, 1);
+        assert_eq!(ride.exit.column, 0);
+    }
+
+    #[test]
+    fn stop_at_edge() {
+        let ride = example_map().ride(slope(3, 1), BoundaryMode::StopAtEdge);
+        assert_eq!(
+            ride,
+            Ride {
+                encountered_trees: 1,
+                exit: Exit {
+                    line: 1,
+                    column: 3,
+                    edge: Edge::Right,
+                    laps: 1,
+                },
+            }
+        );
+
+        let ride = example_map().ride(slope(-1, 1), BoundaryMode::StopAtEdge);
+        assert_eq!(ride.exit.edge, Edge::Left);
+        assert_eq!((ride.exit.line, ride.exit.column), (0, 0));
+
+        let ride = example_map().ride(slope(1, 1), BoundaryMode::StopAtEdge);
+        assert_eq!(ride.encountered_trees, 0);
+        assert_eq!(ride.exit.edge, Edge::Bottom);
+        assert_eq!((ride.exit.line, ride.exit.column), (3, 3));
+    }
+
+    #[test]
+    fn path() {
+        // Path of the first lap: (1, 3), (2, 1), (3, 4).
+        let (ride, path) = example_map().ride_path(slope(3, 1), BoundaryMode::Torus { laps: 2 });
+        assert_eq!(
+            ride,
+            example_map().ride(slope(3, 1), BoundaryMode::Torus { laps: 2 })
+        );
+        assert_eq!(
+            path,
+            vec![(1, 3), (2, 1), (3, 4), (0, 2), (1, 0), (2, 3), (3, 1)]
+        );
+
+        let (ride, path) = example_map().ride_path(slope(3, 1), BoundaryMode::StopAtEdge);
+        assert_eq!(ride.exit.edge, Edge::Right);
+        assert_eq!(path, vec![(1, 3)]);
+    }
+
+    #[test]
+    fn parse_boundary_mode() {
+        assert_eq!("wrap".parse(), Ok(BoundaryMode::HorizontalWrap));
+        assert_eq!("torus:3".parse(), Ok(BoundaryMode::Torus { laps: 3 }));
+        assert_eq!("clamp".parse(), Ok(BoundaryMode::Clamp));
+        assert_eq!("stop".parse(), Ok(BoundaryMode::StopAtEdge));
+        for invalid_name in ["torus", "torus:-1", "torus:", "Wrap", ""] {
+            assert!(
+                invalid_name.parse::<BoundaryMode>().is_err(),
+                "{}",
+                invalid_name
+            );
+        }
+        assert_eq!(Edge::Bottom.to_string(), "bottom");
+    }
+
+    #[test]
+    fn empty_map() {
+        let empty_map = Vec::<String>::new().into_iter().collect::<TreeIndex>();
+        for boundary_mode in [
+            BoundaryMode::HorizontalWrap,
+            BoundaryMode::Torus { laps: 3 },
+            BoundaryMode::Clamp,
+            BoundaryMode::StopAtEdge,
+        ] {
+            let ride = empty_map.ride(slope(3, 1), boundary_mode);
+            assert_eq!(
+                ride,
+                Ride {
+                    encountered_trees: 0,
+                    exit: Exit {
+                        line: 0,
+                        column: 0,
+                        edge: Edge::Bottom,
+                        laps: 1,
+                    },
+                },
+                "{:?}",
+                boundary_mode
+            );
+        }
+
+        let ride = empty_map.ride(slope(3, 1), BoundaryMode::Torus { laps: 0 });
+        assert_eq!(ride.exit.laps, 0);
+    }
+
+    #[test]
+    fn blank_lines() {
+        let map = vec![
+            String::from("....."),
+            String::from(""),
+            String::from("...#."),
+            String::from("....#"),
+        ]
+        .into_iter()
+        .collect::<TreeIndex>();
+
+        // Path: (2, 3), (3, 1), second lap: (0, 4), (2, 2), (3, 0).
+        let ride = map.ride(slope(3, 1), BoundaryMode::Torus { laps: 2 });
+        assert_eq!(ride.encountered_trees, 1);
+        assert_eq!(
+            (ride.exit.line, ride.exit.column, ride.exit.laps),
+            (3, 0, 2)
+        );
+    }
+}
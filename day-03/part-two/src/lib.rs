@@ -0,0 +1,539 @@
+mod boundary;
+mod terrain;
+mod tree_index;
+
+pub use boundary::{BoundaryMode, Edge, Exit, Ride};
+pub use terrain::{Terrain, TerrainLegend, TrajectoryReport, UnknownSymbol};
+pub use tree_index::TreeIndex;
+
+#[derive(Debug, Clone, Copy)]
+pub struct MovementScheme {
+    /// Number of columns to move, negative values move to the left.
+    pub right: isize,
+    /// Number of lines to move, must be at least one.
+    pub down: usize,
+}
+
+/// Sequence of moves which is repeated until the bottom of the map.
+/// Example: `[(3, 1), (1, 2)]` is a zig-zag descent where the toboggan
+/// alternately moves right 3 and down 1, then right 1 and down 2.
+#[derive(Debug, Clone)]
+pub struct Trajectory(Vec<MovementScheme>);
+
+pub trait TobogganTrajectory {
+    fn calculate_encountered_trees<T: Into<Trajectory>>(self, trajectory: T) -> usize;
+    fn calculate_trajectory_report<T: Into<Trajectory>>(
+        self,
+        trajectory: T,
+        legend: &TerrainLegend,
+    ) -> TrajectoryReport;
+    /// Line number and column of every field where the toboggan stops, in
+    /// the order of the ride. Both are numbered from 0.
+    fn calculate_path<T: Into<Trajectory>>(self, trajectory: T) -> Vec<(usize, usize)>;
+}
+
+impl Trajectory {
+    pub fn new(moves: Vec<MovementScheme>) -> Result<Self, String> {
+        if moves.is_empty() {
+            return Err(String::from("trajectory must contain at least one move"));
+        }
+        if let Some(i) = moves.iter().position(|movement| movement.down == 0) {
+            let error_message = format!("move {} must go down at least one line", i + 1);
+            return Err(error_message);
+        }
+        Ok(Self(moves))
+    }
+
+    /// Calls `visit` with line number, column and symbol of every field
+    /// where the toboggan stops.
+    fn visit_fields<I, F>(&self, lines: I, mut visit: F)
+    where
+        I: Iterator<Item = String>,
+        F: FnMut(usize, usize, char),
+    {
+        // This `unwrap()` is safe because the trajectory always contains at
+        // least one move.
+        let mut moves = self.0.iter().cycle();
+        let mut movement = moves.next().unwrap();
+        let mut line_to_check = movement.down;
+        let mut position_to_check = 0;
+
+        // We don't need to check lines between moves. In these lines there
+        // is only a transition to the next line.
+        for (line_number, line) in lines.enumerate() {
+            if line_number != line_to_check {
+                continue;
+            }
+
+            // The map repeats to the left and to the right, so the position
+            // wraps in both directions. Blank lines (e.g. at the end of the
+            // file) don't contain any field to visit.
+            let line_length = line.chars().count() as isize;
+            if line_length > 0 {
+                position_to_check =
+                    (position_to_check as isize + movement.right).rem_euclid(line_length) as usize;
+                if let Some(symbol) = line.chars().nth(position_to_check) {
+                    visit(line_number, position_to_check, symbol);
+                }
+            }
+
+            movement = moves.next().unwrap();
+            line_to_check += movement.down;
+        }
+    }
+}
+
+impl TryFrom<MovementScheme> for Trajectory {
+    type Error = String;
+
+    /// Trajectory with a single move, which must go down at least one line.
+    fn try_from(movement_scheme: MovementScheme) -> Result<Self, Self::Error> {
+        Self::new(vec![movement_scheme])
+    }
+}
+
+/// Trajectory with a single move, for the tests.
+#[cfg(test)]
+fn slope(right: isize, down: usize) -> Trajectory {
+    Trajectory::try_from(MovementScheme { right, down }).unwrap()
+}
+
+impl<I> TobogganTrajectory for I
+where
+    I: Iterator<Item = String>,
+{
+    fn calculate_encountered_trees<T: Into<Trajectory>>(self, trajectory: T) -> usize {
+        // Tree is defined as character '#'
+        const TREE: char = '#';
+
+        let mut encountered_trees = 0;
+        trajectory.into().visit_fields(self, |_, _, symbol| {
+            if symbol == TREE {
+                encountered_trees += 1;
+            }
+        });
+        encountered_trees
+    }
+
+    fn calculate_trajectory_report<T: Into<Trajectory>>(
+        self,
+        trajectory: T,
+        legend: &TerrainLegend,
+    ) -> TrajectoryReport {
+        let mut report = TrajectoryReport::default();
+        trajectory
+            .into()
+            .visit_fields(self, |line_number, column, symbol| {
+                report.visit(legend, line_number, column, symbol);
+            });
+        report
+    }
+
+    fn calculate_path<T: Into<Trajectory>>(self, trajectory: T) -> Vec<(usize, usize)> {
+        let mut path = Vec::new();
+        trajectory
+            .into()
+            .visit_fields(self, |line_number, column, _| {
+                path.push((line_number, column));
+            });
+        path
+    }
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::MovementScheme;
+    use super::TobogganTrajectory;
+    use super::{slope, Trajectory};
+    use super::{Terrain, TerrainLegend, UnknownSymbol};
+
+    // Examples in `examples/day03` of the repository.
+    const MAP: &str = include_str!("../../../examples/day03/map.txt");
+    const WITH_WRAPPING: &str = include_str!("../../../examples/day03/with-wrapping.txt");
+    const WITHOUT_WRAPPING: &str = include_str!("../../../examples/day03/without-wrapping.txt");
+
+    fn example(raw_map: &str) -> Vec<String> {
+        raw_map.lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn without_wrapping() {
+        let input = example(WITHOUT_WRAPPING);
+        let movement_scheme = slope(3, 1);
+        let encountered_trees = input
+            .into_iter()
+            .calculate_encountered_trees(movement_scheme);
+
+        assert_eq!(encountered_trees, 3);
+    }
+
+    #[test]
+    fn with_wrapping() {
+        let input = example(WITH_WRAPPING);
+        let movement_scheme = slope(3, 1);
+        let encountered_trees = input
+            .into_iter()
+            .calculate_encountered_trees(movement_scheme);
+
+        assert_eq!(encountered_trees, 4);
+    }
+
+    #[test]
+    fn example_data() {
+        let input = example(MAP);
+        let movement_scheme = slope(3, 1);
+        let encountered_trees = input
+            .into_iter()
+            .calculate_encountered_trees(movement_scheme);
+
+        assert_eq!(encountered_trees, 7);
+    }
+
+    #[test]
+    fn path() {
+        let input = example(MAP);
+        let movement_scheme = slope(3, 1);
+        let path = input.clone().into_iter().calculate_path(movement_scheme);
+
+        assert_eq!(path.len(), input.len() - 1);
+        assert_eq!(&path[..4], &[(1, 3), (2, 6), (3, 9), (4, 1)]);
+        let trees = path
+            .iter()
+            .filter(|(line, column)| input[*line].as_bytes()[*column] == b'#')
+            .count();
+        assert_eq!(trees, 7);
+    }
+
+    #[test]
+    fn single_move_trajectory() {
+        let input = example(MAP);
+        let trajectory = Trajectory::new(vec![MovementScheme { right: 3, down: 1 }]).unwrap();
+        let encountered_trees = input
+            .clone()
+            .into_iter()
+            .calculate_encountered_trees(trajectory);
+        let movement_scheme = slope(3, 1);
+        let expected_trees = input
+            .into_iter()
+            .calculate_encountered_trees(movement_scheme);
+
+        assert_eq!(encountered_trees, expected_trees);
+    }
+
+    #[test]
+    fn zig_zag_trajectory() {
+        let input = example(MAP);
+        let trajectory = Trajectory::new(vec![
+            MovementScheme { right: 3, down: 1 },
+            MovementScheme { right: 1, down: 2 },
+        ])
+        .unwrap();
+        let encountered_trees = input.into_iter().calculate_encountered_trees(trajectory);
+
+        assert_eq!(encountered_trees, 2);
+    }
+
+    #[test]
+    fn left_moving_trajectory() {
+        let input = example(MAP);
+        let movement_scheme = slope(-3, 1);
+        let encountered_trees = input
+            .clone()
+            .into_iter()
+            .calculate_encountered_trees(movement_scheme);
+        assert_eq!(encountered_trees, 3);
+
+        let trajectory = Trajectory::new(vec![
+            MovementScheme { right: -1, down: 1 },
+            MovementScheme { right: 1, down: 1 },
+        ])
+        .unwrap();
+        let encountered_trees = input.into_iter().calculate_encountered_trees(trajectory);
+        assert_eq!(encountered_trees, 4);
+    }
+
+    #[test]
+    fn invalid_trajectory() {
+        assert!(Trajectory::new(vec![]).is_err());
+        assert_eq!(
+            Trajectory::try_from(MovementScheme { right: 3, down: 0 }).unwrap_err(),
+            "move 1 must go down at least one line"
+        );
+        assert!(Trajectory::new(vec![
+            MovementScheme { right: 3, down: 1 },
+            MovementScheme { right: 1, down: 0 },
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn terrain_report() {
+        let input = vec![
+            String::from("......."),
+            String::from("...~..."),
+            String::from("......#"),
+            String::from("..^...."),
+            String::from(".....@."),
+        ];
+        let legend = vec![
+            Terrain {
+                symbol: '.',
+                name: String::from("open"),
+                cost: 0,
+            },
+            Terrain {
+                symbol: '#',
+                name: String::from("tree"),
+                cost: 1,
+            },
+            Terrain {
+                symbol: '~',
+                name: String::from("ice"),
+                cost: 2,
+            },
+            Terrain {
+                symbol: '^',
+                name: String::from("rock"),
+                cost: 5,
+            },
+        ]
+        .into_iter()
+        .collect::<TerrainLegend>();
+        let movement_scheme = slope(3, 1);
+        let report = input
+            .into_iter()
+            .calculate_trajectory_report(movement_scheme, &legend);
+
+        assert_eq!(report.encountered('#'), 1);
+        assert_eq!(report.encountered('~'), 1);
+        assert_eq!(report.encountered('^'), 1);
+        assert_eq!(report.encountered('.'), 0);
+        assert_eq!(report.weighted_cost, 8);
+        assert_eq!(
+            report.unknown_symbols,
+            vec![UnknownSymbol {
+                line: 4,
+                column: 5,
+                symbol: '@',
+            }]
+        );
+    }
+}
+
+#[cfg(test)]
+mod edge_cases {
+    use super::{slope, BoundaryMode, TobogganTrajectory, TreeIndex};
+
+    #[test]
+    fn down_larger_than_map_height() {
+        let input = vec![
+            String::from("#.#"),
+            String::from("###"),
+            String::from("###"),
+        ];
+        let encountered_trees = input
+            .clone()
+            .into_iter()
+            .calculate_encountered_trees(slope(1, 3));
+        assert_eq!(encountered_trees, 0);
+
+        let tree_index = input.into_iter().collect::<TreeIndex>();
+        assert_eq!(tree_index.count_trees(slope(1, 7)), 0);
+    }
+
+    #[test]
+    fn single_column_map() {
+        let input = vec![
+            String::from("."),
+            String::from("#"),
+            String::from("."),
+            String::from("#"),
+        ];
+        let encountered_trees = input
+            .clone()
+            .into_iter()
+            .calculate_encountered_trees(slope(3, 1));
+        assert_eq!(encountered_trees, 2);
+
+        let tree_index = input.into_iter().collect::<TreeIndex>();
+        assert_eq!(tree_index.count_trees(slope(-5, 1)), 2);
+    }
+
+    #[test]
+    fn empty_input() {
+        let input: Vec<String> = vec![];
+        let encountered_trees = input
+            .clone()
+            .into_iter()
+            .calculate_encountered_trees(slope(3, 1));
+        assert_eq!(encountered_trees, 0);
+
+        let tree_index = input.into_iter().collect::<TreeIndex>();
+        assert_eq!(tree_index.height(), 0);
+        assert_eq!(tree_index.count_trees(slope(3, 1)), 0);
+        let ride = tree_index.ride(slope(3, 1), BoundaryMode::Torus { laps: 3 });
+        assert_eq!(ride.encountered_trees, 0);
+    }
+
+    #[test]
+    fn trailing_blank_lines() {
+        let input = vec![
+            String::from("....."),
+            String::from("...#."),
+            String::from("#...."),
+            String::from(""),
+            String::from(""),
+        ];
+        let encountered_trees = input
+            .clone()
+            .into_iter()
+            .calculate_encountered_trees(slope(3, 1));
+        assert_eq!(encountered_trees, 1);
+
+        let tree_index = input.into_iter().collect::<TreeIndex>();
+        assert_eq!(tree_index.height(), 3);
+        assert_eq!(tree_index.count_trees(slope(3, 1)), 1);
+        let ride = tree_index.ride(slope(3, 1), BoundaryMode::StopAtEdge);
+        assert_eq!((ride.exit.line, ride.exit.column), (1, 3));
+    }
+}
+
+#[cfg(test)]
+mod oracle {
+    use super::{
+        BoundaryMode, MovementScheme, TerrainLegend, TobogganTrajectory, Trajectory, TreeIndex,
+    };
+
+    /// Xorshift generator, so every run checks the same maps.
+    struct Random(u64);
+
+    impl Random {
+        fn below(&mut self, bound: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % bound as u64) as usize
+        }
+    }
+
+    /// Reference simulator: walks down the map one move at a time, without
+    /// any shortcuts.
+    fn count_trees(map: &[String], moves: &[(isize, usize)]) -> usize {
+        let fields = map
+            .iter()
+            .map(|line| line.chars().collect::<Vec<char>>())
+            .collect::<Vec<Vec<char>>>();
+        let mut line = 0;
+        let mut column: isize = 0;
+        let mut encountered_trees = 0;
+        for (right, down) in moves.iter().cycle() {
+            line += down;
+            let line_fields = match fields.get(line) {
+                Some(line_fields) => line_fields,
+                None => break,
+            };
+            if line_fields.is_empty() {
+                continue;
+            }
+            column = (column + right).rem_euclid(line_fields.len() as isize);
+            if line_fields[column as usize] == '#' {
+                encountered_trees += 1;
+            }
+        }
+        encountered_trees
+    }
+
+    fn random_map(random: &mut Random) -> Vec<String> {
+        let height = random.below(30);
+        let width = 1 + random.below(20);
+        let density = 1 + random.below(4);
+        let mut map = (0..height)
+            .map(|_| {
+                (0..width)
+                    .map(|_| if random.below(density) == 0 { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>();
+        for _ in 0..random.below(3) {
+            map.push(String::new());
+        }
+        map
+    }
+
+    fn random_moves(random: &mut Random) -> Vec<(isize, usize)> {
+        (0..1 + random.below(3))
+            .map(|_| (random.below(51) as isize - 25, 1 + random.below(35)))
+            .collect()
+    }
+
+    fn trajectory(moves: &[(isize, usize)]) -> Trajectory {
+        let moves = moves
+            .iter()
+            .map(|&(right, down)| MovementScheme { right, down })
+            .collect::<Vec<MovementScheme>>();
+        Trajectory::new(moves).unwrap()
+    }
+
+    #[test]
+    fn random_maps_and_slopes() {
+        let mut random = Random(0x9e37_79b9_7f4a_7c15);
+        let legend = TerrainLegend::default();
+        for _ in 0..1000 {
+            let map = random_map(&mut random);
+            let moves = random_moves(&mut random);
+            let expected_trees = count_trees(&map, &moves);
+            let context = format!("map: {:?}, moves: {:?}", map, moves);
+
+            let encountered_trees = map
+                .clone()
+                .into_iter()
+                .calculate_encountered_trees(trajectory(&moves));
+            assert_eq!(encountered_trees, expected_trees, "{}", context);
+
+            let report = map
+                .clone()
+                .into_iter()
+                .calculate_trajectory_report(trajectory(&moves), &legend);
+            assert_eq!(
+                report.encountered(TerrainLegend::TREE),
+                expected_trees,
+                "{}",
+                context
+            );
+            assert!(report.unknown_symbols.is_empty(), "{}", context);
+
+            let tree_index = map.into_iter().collect::<TreeIndex>();
+            let encountered_trees = tree_index.count_trees(trajectory(&moves));
+            assert_eq!(encountered_trees, expected_trees, "{}", context);
+            let ride = tree_index.ride(trajectory(&moves), BoundaryMode::HorizontalWrap);
+            assert_eq!(ride.encountered_trees, expected_trees, "{}", context);
+        }
+    }
+}
+
+#[cfg(test)]
+mod regression {
+    use super::{slope, TobogganTrajectory, TreeIndex};
+
+    fn input() -> impl Iterator<Item = String> {
+        include_str!("../../input.dat").lines().map(String::from)
+    }
+
+    #[test]
+    fn single_slope() {
+        let encountered_trees = input().calculate_encountered_trees(slope(3, 1));
+        assert_eq!(encountered_trees, 169);
+    }
+
+    #[test]
+    fn multiple_slopes() {
+        let slopes = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+        let tree_index = input().collect::<TreeIndex>();
+        for ((right, down), expected_trees) in slopes.into_iter().zip([87, 169, 99, 98, 53]) {
+            let encountered_trees = input().calculate_encountered_trees(slope(right, down));
+            assert_eq!(encountered_trees, expected_trees);
+            let encountered_trees = tree_index.count_trees(slope(right, down));
+            assert_eq!(encountered_trees, expected_trees);
+        }
+    }
+}
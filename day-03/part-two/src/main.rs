@@ -1,32 +1,63 @@
-use std::env;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-
-struct MovementScheme {
-    right: usize,
-    down: usize,
-}
-
-trait TobogganTrajectory {
-    fn calculate_encountered_trees(self, movement_scheme: MovementScheme) -> usize;
+use aoc_common::{phase, Args, Error, Source};
+use part_two::{
+    BoundaryMode, MovementScheme, Ride, TerrainLegend, TobogganTrajectory, Trajectory, TreeIndex,
+};
+use serde_json::json;
+use std::convert::TryFrom;
+use std::process::ExitCode;
+
+const USAGE: &str = "Program must be executed with arguments: [input] [legend_file (optional)] \
+                     [--report json] [--boundary wrap|torus:N|clamp|stop]";
+
+fn main() -> ExitCode {
+    aoc_common::run(solve)
 }
 
-fn main() {
-    let args = env::args().collect::<Vec<String>>();
-    if args.len() != 2 {
-        eprintln!("Program must be executed with one argument: [file_name]");
-        return;
-    }
-
-    // First argument is name of binary file.
-    // Usefully is second argument which is `file name` with data.
-    let file_name = match args.get(1) {
-        Some(name) => name,
-        None => {
-            eprintln!("unable to get file name");
-            return;
+fn solve() -> Result<(), Error> {
+    let args = Args::from_env(USAGE, 0..=5)?;
+    let usage_error = || Error::Usage(USAGE.to_string());
+
+    // Arguments after the input are optional `file name` with terrain legend
+    // and pairs of option and its value. `--report json` prints every field
+    // visited on every slope instead of the summary. `--boundary` selects
+    // what happens at the edges of the map and prints where the toboggan
+    // left it, instead of costs of the terrains.
+    let (legend_file_name, options) = match args.rest.split_first() {
+        Some((legend_file_name, options)) if !legend_file_name.starts_with("--") => {
+            (Some(legend_file_name), options)
         }
+        _ => (None, args.rest.as_slice()),
     };
+    let mut report = false;
+    let mut boundary_mode = None;
+    for option in options.chunks(2) {
+        match option {
+            [name, format] if name == "--report" && format == "json" => report = true,
+            [name, mode] if name == "--boundary" => {
+                boundary_mode = Some(
+                    mode.parse::<BoundaryMode>()
+                        .map_err(|e| Error::Usage(format!("{}\n{}", e, USAGE)))?,
+                )
+            }
+            _ => return Err(usage_error()),
+        }
+    }
+    // Rides with a boundary mode count only trees, so terrains of the legend
+    // wouldn't be used.
+    if legend_file_name.is_some() && boundary_mode.is_some() {
+        return Err(Error::Usage(String::from(
+            "`--boundary` can't be used together with a legend file",
+        )));
+    }
+
+    let (legend, lines) = phase("parse", || {
+        let legend = match legend_file_name {
+            Some(legend_file_name) => parse_legend_file(legend_file_name)?,
+            None => TerrainLegend::default(),
+        };
+        // Input is read once, so it can also come from standard input.
+        Ok::<_, Error>((legend, args.input.lines()?))
+    })?;
 
     let movement_schemes = vec![
         MovementScheme { right: 1, down: 1 },
@@ -35,23 +66,61 @@ fn main() {
         MovementScheme { right: 7, down: 1 },
         MovementScheme { right: 1, down: 2 },
     ];
-    let mut trees_on_the_slopes = Vec::with_capacity(movement_schemes.len());
-    for (i, movement_scheme) in movement_schemes.into_iter().enumerate() {
-        let file = match File::open(file_name) {
-            Ok(file) => file,
-            Err(e) => {
-                eprintln!("unable to open file for slope {}, {}", i + 1, e);
-                continue;
-            }
-        };
-
-        let reader = BufReader::new(file);
-        let encountered_trees = reader
-            .lines()
+    // Every slope must go down at least one line.
+    let trajectories = movement_schemes
+        .iter()
+        .map(|movement_scheme| Trajectory::try_from(*movement_scheme))
+        .collect::<Result<Vec<Trajectory>, String>>()
+        .map_err(|e| Error::Validation(format!("invalid slope, {}", e)))?;
+    if report {
+        println!(
+            "{}",
+            json_report(
+                &lines,
+                &movement_schemes,
+                trajectories,
+                &legend,
+                boundary_mode
+            )
+        );
+        return Ok(());
+    }
+    if let Some(boundary_mode) = boundary_mode {
+        return print_rides(lines, trajectories, boundary_mode);
+    }
+    let reports = phase("solve", || {
+        trajectories
             .into_iter()
-            .map(|line| line.unwrap())
-            .calculate_encountered_trees(movement_scheme);
-        trees_on_the_slopes.push(encountered_trees);
+            .map(|trajectory| {
+                lines
+                    .iter()
+                    .cloned()
+                    .calculate_trajectory_report(trajectory, &legend)
+            })
+            .collect::<Vec<_>>()
+    });
+    let mut trees_on_the_slopes = Vec::with_capacity(reports.len());
+    for (i, report) in reports.into_iter().enumerate() {
+        for unknown in report.unknown_symbols.iter() {
+            eprintln!(
+                "slope {}: unknown symbol '{}' at line {}, column {}",
+                i + 1,
+                unknown.symbol,
+                unknown.line + 1,
+                unknown.column + 1
+            );
+        }
+        let terrains = legend
+            .terrains()
+            .map(|terrain| format!("{} = {}", terrain.name, report.encountered(terrain.symbol)))
+            .collect::<Vec<String>>();
+        println!(
+            "Slope {}: {}, weighted cost = {}",
+            i + 1,
+            terrains.join(", "),
+            report.weighted_cost
+        );
+        trees_on_the_slopes.push(report.encountered(TerrainLegend::TREE));
     }
     let encountered_trees: usize = trees_on_the_slopes.iter().product();
 
@@ -59,96 +128,93 @@ fn main() {
         "Encountered trees on slopes: {:?}, product all of them = {}",
         trees_on_the_slopes, encountered_trees
     );
+    Ok(())
 }
 
-impl<I> TobogganTrajectory for I
-where
-    I: Iterator<Item = String>,
-{
-    fn calculate_encountered_trees(self, movement_scheme: MovementScheme) -> usize {
-        // Tree is defined as character '#'
-        const TREE: char = '#';
-
-        let mut position_to_check = 0;
-
-        // `Skip` because we don't need to check first `n` lines.
-        // In these lines there is only a transition to the next line.
-        // There is no move to the right.
-        // We can check lines that are a combination of defined movement.
-        self.skip(movement_scheme.down)
-            .step_by(movement_scheme.down)
-            .filter(|line| {
-                let line_length = line.len();
-                position_to_check = (position_to_check + movement_scheme.right) % line_length;
-                if let Some(symbol) = line.chars().nth(position_to_check) {
-                    symbol == TREE
-                } else {
-                    false
-                }
-            })
-            .count()
-    }
-}
-
-#[cfg(test)]
-mod example_data {
-    use super::MovementScheme;
-    use super::TobogganTrajectory;
-
-    #[test]
-    fn without_wrapping() {
-        let input = vec![
-            String::from("............."),
-            String::from("............."),
-            String::from("......#......"),
-            String::from(".........#..."),
-            String::from("............#"),
-        ];
-        let movement_scheme = MovementScheme { right: 3, down: 1 };
-        let encountered_trees = input
+/// Prints trees encountered on every slope and where the toboggan left the
+/// map.
+fn print_rides(
+    lines: Vec<String>,
+    trajectories: Vec<Trajectory>,
+    boundary_mode: BoundaryMode,
+) -> Result<(), Error> {
+    let rides = phase("solve", || {
+        let tree_index = lines.into_iter().collect::<TreeIndex>();
+        trajectories
             .into_iter()
-            .calculate_encountered_trees(movement_scheme);
-
-        assert_eq!(encountered_trees, 3);
+            .map(|trajectory| tree_index.ride(trajectory, boundary_mode))
+            .collect::<Vec<Ride>>()
+    });
+    let mut trees_on_the_slopes = Vec::with_capacity(rides.len());
+    for (i, ride) in rides.into_iter().enumerate() {
+        println!(
+            "Slope {}: trees = {}, exit at line {}, column {}, {} edge, laps = {}",
+            i + 1,
+            ride.encountered_trees,
+            ride.exit.line + 1,
+            ride.exit.column + 1,
+            ride.exit.edge,
+            ride.exit.laps
+        );
+        trees_on_the_slopes.push(ride.encountered_trees);
     }
+    let encountered_trees: usize = trees_on_the_slopes.iter().product();
 
-    #[test]
-    fn with_wrapping() {
-        let input = vec![
-            String::from("......."),
-            String::from("...#..."),
-            String::from("......#"),
-            String::from("..#...."),
-            String::from(".....#."),
-        ];
-        let movement_scheme = MovementScheme { right: 3, down: 1 };
-        let encountered_trees = input
-            .into_iter()
-            .calculate_encountered_trees(movement_scheme);
-
-        assert_eq!(encountered_trees, 4);
-    }
+    println!(
+        "Encountered trees on slopes: {:?}, product all of them = {}",
+        trees_on_the_slopes, encountered_trees
+    );
+    Ok(())
+}
 
-    #[test]
-    fn example_data() {
-        let input = vec![
-            String::from("..##.........##.........##.........##.........##.........##......."),
-            String::from("#...#...#..#...#...#..#...#...#..#...#...#..#...#...#..#...#...#.."),
-            String::from(".#....#..#..#....#..#..#....#..#..#....#..#..#....#..#..#....#..#."),
-            String::from("..#.#...#.#..#.#...#.#..#.#...#.#..#.#...#.#..#.#...#.#..#.#...#.#"),
-            String::from(".#...##..#..#...##..#..#...##..#..#...##..#..#...##..#..#...##..#."),
-            String::from("..#.##.......#.##.......#.##.......#.##.......#.##.......#.##....."),
-            String::from(".#.#.#....#.#.#.#....#.#.#.#....#.#.#.#....#.#.#.#....#.#.#.#....#"),
-            String::from(".#........#.#........#.#........#.#........#.#........#.#........#"),
-            String::from("#.##...#...#.##...#...#.##...#...#.##...#...#.##...#...#.##...#..."),
-            String::from("#...##....##...##....##...##....##...##....##...##....##...##....#"),
-            String::from(".#..#...#.#.#..#...#.#.#..#...#.#.#..#...#.#.#..#...#.#.#..#...#.#"),
-        ];
-        let movement_scheme = MovementScheme { right: 3, down: 1 };
-        let encountered_trees = input
-            .into_iter()
-            .calculate_encountered_trees(movement_scheme);
+/// Report as a JSON object with every field visited on every slope and the
+/// number of encountered trees. With a boundary mode every slope also has
+/// the field where the toboggan left the map, numbered from 0 like the path.
+fn json_report(
+    lines: &[String],
+    movement_schemes: &[MovementScheme],
+    trajectories: Vec<Trajectory>,
+    legend: &TerrainLegend,
+    boundary_mode: Option<BoundaryMode>,
+) -> String {
+    let tree_index = boundary_mode.map(|_| lines.iter().cloned().collect::<TreeIndex>());
+    let slopes = movement_schemes
+        .iter()
+        .zip(trajectories)
+        .map(|(movement_scheme, trajectory)| {
+            if let (Some(boundary_mode), Some(tree_index)) = (boundary_mode, &tree_index) {
+                let (ride, path) = tree_index.ride_path(trajectory, boundary_mode);
+                return json!({
+                    "right": movement_scheme.right,
+                    "down": movement_scheme.down,
+                    "trees": ride.encountered_trees,
+                    "path": path,
+                    "exit": {
+                        "line": ride.exit.line,
+                        "column": ride.exit.column,
+                        "edge": ride.exit.edge.to_string(),
+                        "laps": ride.exit.laps,
+                    },
+                });
+            }
+            let path = lines.iter().cloned().calculate_path(trajectory.clone());
+            let report = lines
+                .iter()
+                .cloned()
+                .calculate_trajectory_report(trajectory, legend);
+            json!({
+                "right": movement_scheme.right,
+                "down": movement_scheme.down,
+                "trees": report.encountered(TerrainLegend::TREE),
+                "path": path,
+            })
+        })
+        .collect::<Vec<serde_json::Value>>();
+    json!({ "slopes": slopes }).to_string()
+}
 
-        assert_eq!(encountered_trees, 7);
-    }
+fn parse_legend_file(file_name: &str) -> Result<TerrainLegend, Error> {
+    let lines = Source::from_argument(file_name).lines()?;
+    TerrainLegend::try_from(lines)
+        .map_err(|e| Error::Parse(format!("legend file {}, {}", file_name, e)))
 }
@@ -0,0 +1,250 @@
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+// Example legend line: "# tree 1"
+// `#` is the symbol on the map, `tree` is the name of the terrain and `1` is
+// the cost of passing through a single field with this terrain.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Terrain {
+    pub symbol: char,
+    pub name: String,
+    pub cost: u32,
+}
+
+/// Set of terrains which can be found on the map, indexed by their symbol.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TerrainLegend(BTreeMap<char, Terrain>);
+
+/// Field on the trajectory with a symbol which is not described by the legend.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownSymbol {
+    /// Line of the map, numbered from 0.
+    pub line: usize,
+    /// Column of the map, numbered from 0.
+    pub column: usize,
+    pub symbol: char,
+}
+
+/// Summary of all fields visited on a single trajectory.
+#[derive(Debug, Default, PartialEq)]
+pub struct TrajectoryReport {
+    /// Number of visited fields for each known terrain symbol.
+    pub encountered: BTreeMap<char, usize>,
+    /// Sum of the costs of all visited fields with a known terrain.
+    pub weighted_cost: u64,
+    pub unknown_symbols: Vec<UnknownSymbol>,
+}
+
+impl TryFrom<String> for Terrain {
+    type Error = String;
+
+    fn try_from(line: String) -> Result<Self, Self::Error> {
+        let split_line = line.split_ascii_whitespace().collect::<Vec<&str>>();
+        // Line with terrain must be split into vector with three elements.
+        // Example: "# tree 1" -> ["#", "tree", "1"]
+        if split_line.len() != 3 {
+            let error_message = format!("problem with split raw line: {}", line);
+            return Err(error_message);
+        }
+
+        // This `unwrap()` is safe because we've checked the size of the
+        // vector before.
+        let raw_symbol = split_line.first().unwrap();
+        let mut symbols = raw_symbol.chars();
+        let symbol = match (symbols.next(), symbols.next()) {
+            (Some(symbol), None) => symbol,
+            _ => {
+                let error_message = format!("symbol must be a single character: {}", raw_symbol);
+                return Err(error_message);
+            }
+        };
+        let name = split_line.get(1).unwrap().to_string();
+        let cost = split_line
+            .get(2)
+            .unwrap()
+            .parse::<u32>()
+            .map_err(|e| format!("unable to parse cost, {}", e))?;
+        Ok(Self { symbol, name, cost })
+    }
+}
+
+impl TerrainLegend {
+    /// Symbol of an open field.
+    pub const OPEN: char = '.';
+    /// Symbol of a tree.
+    pub const TREE: char = '#';
+
+    pub fn get(&self, symbol: char) -> Option<&Terrain> {
+        self.0.get(&symbol)
+    }
+
+    pub fn terrains(&self) -> impl Iterator<Item = &Terrain> {
+        self.0.values()
+    }
+}
+
+impl Default for TerrainLegend {
+    /// Legend with the terrains from the original puzzle: open fields and
+    /// trees, where only trees are counted into the cost.
+    fn default() -> Self {
+        vec![
+            Terrain {
+                symbol: Self::OPEN,
+                name: String::from("open"),
+                cost: 0,
+            },
+            Terrain {
+                symbol: Self::TREE,
+                name: String::from("tree"),
+                cost: 1,
+            },
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+impl TryFrom<Vec<String>> for TerrainLegend {
+    type Error = String;
+
+    /// Parses the lines of a legend file. Every symbol may be described only
+    /// once. Blank lines and comments starting with `//` are skipped, but
+    /// still counted in line numbers of errors.
+    fn try_from(lines: Vec<String>) -> Result<Self, Self::Error> {
+        let mut terrains: BTreeMap<char, Terrain> = BTreeMap::new();
+        for (i, line) in lines.into_iter().enumerate() {
+            let trimmed_line = line.trim();
+            if trimmed_line.is_empty() || trimmed_line.starts_with("//") {
+                continue;
+            }
+            let terrain = Terrain::try_from(line).map_err(|e| format!("line {}: {}", i + 1, e))?;
+            if let Some(earlier) = terrains.get(&terrain.symbol) {
+                let error_message = format!(
+                    "line {}: symbol '{}' is already used by terrain {}",
+                    i + 1,
+                    terrain.symbol,
+                    earlier.name
+                );
+                return Err(error_message);
+            }
+            terrains.insert(terrain.symbol, terrain);
+        }
+        Ok(TerrainLegend(terrains))
+    }
+}
+
+/// Later terrains replace earlier ones with the same symbol. Legends read from
+/// a file are checked for duplicates by `TerrainLegend::try_from` instead.
+impl FromIterator<Terrain> for TerrainLegend {
+    fn from_iter<T>(iter: T) -> Self
+    where
+        T: IntoIterator<Item = Terrain>,
+    {
+        let mut terrains = BTreeMap::new();
+        for terrain in iter {
+            terrains.insert(terrain.symbol, terrain);
+        }
+        TerrainLegend(terrains)
+    }
+}
+
+impl TrajectoryReport {
+    /// Records a single visited field.
+    pub(crate) fn visit(
+        &mut self,
+        legend: &TerrainLegend,
+        line: usize,
+        column: usize,
+        symbol: char,
+    ) {
+        match legend.get(symbol) {
+            Some(terrain) => {
+                *self.encountered.entry(symbol).or_insert(0) += 1;
+                self.weighted_cost += u64::from(terrain.cost);
+            }
+            None => self.unknown_symbols.push(UnknownSymbol {
+                line,
+                column,
+                symbol,
+            }),
+        }
+    }
+
+    /// Number of visited fields with the given terrain symbol.
+    pub fn encountered(&self, symbol: char) -> usize {
+        self.encountered.get(&symbol).copied().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::{Terrain, TerrainLegend};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn parse_legend() {
+        let input = vec![
+            String::from("# tree 1"),
+            String::from("~ ice 3"),
+            String::from("^ rock 10"),
+        ];
+
+        let legend = TerrainLegend::try_from(input).unwrap();
+        assert_eq!(legend.terrains().count(), 3);
+        assert_eq!(legend.get('~').map(|terrain| terrain.cost), Some(3));
+        assert_eq!(legend.get('.'), None);
+    }
+
+    #[test]
+    fn invalid_legend() {
+        for invalid_line in ["## tree 1", "@ lava", "% swamp -1"] {
+            assert!(Terrain::try_from(String::from(invalid_line)).is_err());
+            let input = vec![String::from("# tree 1"), String::from(invalid_line)];
+            assert!(TerrainLegend::try_from(input)
+                .unwrap_err()
+                .starts_with("line 2: "));
+        }
+    }
+
+    #[test]
+    fn blank_lines_and_comments() {
+        let input = vec![
+            String::from("// Terrains of the northern slopes."),
+            String::from("# tree 1"),
+            String::from(""),
+            String::from("  "),
+            String::from("  // Open fields cost nothing."),
+            String::from(". open 0"),
+            String::from("/ slash 2"),
+            String::from("~ ice"),
+        ];
+
+        assert_eq!(
+            TerrainLegend::try_from(input[..7].to_vec())
+                .unwrap()
+                .terrains()
+                .map(|terrain| terrain.symbol)
+                .collect::<String>(),
+            "#./"
+        );
+        assert!(TerrainLegend::try_from(input)
+            .unwrap_err()
+            .starts_with("line 8: "));
+    }
+
+    #[test]
+    fn duplicate_symbol() {
+        let input = vec![
+            String::from("# tree 1"),
+            String::from("~ ice 3"),
+            String::from("# bush 2"),
+        ];
+
+        assert_eq!(
+            TerrainLegend::try_from(input),
+            Err(String::from(
+                "line 3: symbol '#' is already used by terrain tree"
+            ))
+        );
+    }
+}
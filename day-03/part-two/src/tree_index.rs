@@ -0,0 +1,180 @@
+use crate::{TerrainLegend, Trajectory};
+
+/// Number of fields stored in a single word of the bitset.
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Map preprocessed into per-line tree bitsets.
+///
+/// The map is read only once. Then counting trees on a trajectory is a single
+/// bit lookup per visited line, so many trajectories can be checked without
+/// parsing the map again.
+#[derive(Debug, Default)]
+pub struct TreeIndex {
+    lines: Vec<IndexedLine>,
+    bits: Vec<u64>,
+}
+
+#[derive(Debug)]
+struct IndexedLine {
+    width: usize,
+    // Index of the first word of this line in `TreeIndex::bits`.
+    offset: usize,
+}
+
+impl TreeIndex {
+    /// Number of lines of the map.
+    pub fn height(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Checks if there is a tree at the given field. Columns outside of the
+    /// line are treated as open fields.
+    pub fn is_tree(&self, line: usize, column: usize) -> bool {
+        match self.lines.get(line) {
+            Some(indexed_line) if column < indexed_line.width => {
+                let word = self.bits[indexed_line.offset + column / WORD_BITS];
+                word & (1 << (column % WORD_BITS)) != 0
+            }
+            _ => false,
+        }
+    }
+
+    /// Width of the given line, or `None` if the line is outside of the map.
+    pub fn width(&self, line: usize) -> Option<usize> {
+        self.lines.get(line).map(|indexed_line| indexed_line.width)
+    }
+
+    pub fn count_trees<T: Into<Trajectory>>(&self, trajectory: T) -> usize {
+        let trajectory = trajectory.into();
+        // This `unwrap()` is safe because the trajectory always contains at
+        // least one move.
+        let mut moves = trajectory.0.iter().cycle();
+        let mut movement = moves.next().unwrap();
+        let mut line_to_check = movement.down;
+        let mut position_to_check = 0;
+        let mut encountered_trees = 0;
+
+        while let Some(indexed_line) = self.lines.get(line_to_check) {
+            // Blank lines don't contain any field to visit.
+            let line_length = indexed_line.width as isize;
+            if line_length > 0 {
+                position_to_check =
+                    (position_to_check as isize + movement.right).rem_euclid(line_length) as usize;
+                if self.is_tree(line_to_check, position_to_check) {
+                    encountered_trees += 1;
+                }
+            }
+
+            movement = moves.next().unwrap();
+            line_to_check += movement.down;
+        }
+        encountered_trees
+    }
+}
+
+impl FromIterator<String> for TreeIndex {
+    fn from_iter<T>(iter: T) -> Self
+    where
+        T: IntoIterator<Item = String>,
+    {
+        let mut tree_index = TreeIndex::default();
+        for line in iter {
+            let offset = tree_index.bits.len();
+            let mut width = 0;
+            for (column, symbol) in line.chars().enumerate() {
+                if column % WORD_BITS == 0 {
+                    tree_index.bits.push(0);
+                }
+                if symbol == TerrainLegend::TREE {
+                    tree_index.bits[offset + column / WORD_BITS] |= 1 << (column % WORD_BITS);
+                }
+                width += 1;
+            }
+            tree_index.lines.push(IndexedLine { width, offset });
+        }
+        // Blank lines at the end of the file are not part of the map.
+        while let Some(IndexedLine { width: 0, .. }) = tree_index.lines.last() {
+            tree_index.lines.pop();
+        }
+        tree_index
+    }
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::TreeIndex;
+    use crate::{slope, MovementScheme, TobogganTrajectory, Trajectory};
+
+    fn example_map() -> Vec<String> {
+        vec![
+            String::from("..##......."),
+            String::from("#...#...#.."),
+            String::from(".#....#..#."),
+            String::from("..#.#...#.#"),
+            String::from(".#...##..#."),
+            String::from("..#.##....."),
+            String::from(".#.#.#....#"),
+            String::from(".#........#"),
+            String::from("#.##...#..."),
+            String::from("#...##....#"),
+            String::from(".#..#...#.#"),
+        ]
+    }
+
+    #[test]
+    fn example_data() {
+        let tree_index = example_map().into_iter().collect::<TreeIndex>();
+        let encountered_trees = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)]
+            .into_iter()
+            .map(|(right, down)| tree_index.count_trees(slope(right, down)))
+            .collect::<Vec<usize>>();
+
+        assert_eq!(encountered_trees, vec![2, 7, 3, 4, 2]);
+    }
+
+    #[test]
+    fn matches_line_by_line_simulation() {
+        let tree_index = example_map().into_iter().collect::<TreeIndex>();
+        for right in -12..=12 {
+            for down in 1..=12 {
+                let expected_trees = example_map()
+                    .into_iter()
+                    .calculate_encountered_trees(slope(right, down));
+                let encountered_trees = tree_index.count_trees(slope(right, down));
+                assert_eq!(
+                    encountered_trees, expected_trees,
+                    "slope ({}, {})",
+                    right, down
+                );
+            }
+        }
+
+        let moves = vec![
+            MovementScheme { right: 3, down: 1 },
+            MovementScheme { right: -1, down: 2 },
+        ];
+        let expected_trees = example_map()
+            .into_iter()
+            .calculate_encountered_trees(Trajectory::new(moves).unwrap());
+        let moves = vec![
+            MovementScheme { right: 3, down: 1 },
+            MovementScheme { right: -1, down: 2 },
+        ];
+        let encountered_trees = tree_index.count_trees(Trajectory::new(moves).unwrap());
+        assert_eq!(encountered_trees, expected_trees);
+    }
+
+    #[test]
+    fn wide_lines() {
+        // Lines wider than a single word of the bitset.
+        let mut line = ".".repeat(200);
+        line.replace_range(130..131, "#");
+        let input = vec![".".repeat(200), line];
+        let tree_index = input.into_iter().collect::<TreeIndex>();
+
+        assert!(tree_index.is_tree(1, 130));
+        assert!(!tree_index.is_tree(1, 129));
+        assert_eq!(tree_index.count_trees(slope(130, 1)), 1);
+        assert_eq!(tree_index.count_trees(slope(-70, 1)), 1);
+    }
+}
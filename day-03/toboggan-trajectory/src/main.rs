@@ -1,6 +1,7 @@
-use std::env;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use aoc_common::{phase, Args, Error};
+use std::process::ExitCode;
+
+const USAGE: &str = "Program must be executed with one argument: [input]";
 
 struct MovementScheme {
     right: usize,
@@ -11,39 +12,30 @@ trait TobogganTrajectory {
     fn calculate_encountered_trees(self, movement_scheme: MovementScheme) -> usize;
 }
 
-fn main() {
-    let args = env::args().collect::<Vec<String>>();
-    if args.len() != 2 {
-        eprintln!("Program must be executed with one argument: [file_name]");
-        return;
-    }
-
-    // First argument is name of binary file.
-    // Usefully is second argument which is `file name` with data.
-    let file_name = match args.get(1) {
-        Some(name) => name,
-        None => {
-            eprintln!("unable to get file name");
-            return;
-        }
-    };
+fn main() -> ExitCode {
+    aoc_common::run(solve)
+}
 
-    let file = match File::open(file_name) {
-        Ok(file) => file,
-        Err(e) => {
-            eprintln!("unable to open file, {}", e);
-            return;
-        }
-    };
-    let reader = BufReader::new(file);
+fn solve() -> Result<(), Error> {
+    let args = Args::from_env(USAGE, 0..=0)?;
+    // All lines of the map must have the same width.
+    let lines = phase("parse", || {
+        let grid = args.input.grid()?;
+        let lines = grid
+            .rows()
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<String>>();
+        Ok::<_, Error>(lines)
+    })?;
     let movement_scheme = MovementScheme { right: 3, down: 1 };
-    let encountered_trees = reader
-        .lines()
-        .into_iter()
-        .map(|line| line.unwrap())
-        .calculate_encountered_trees(movement_scheme);
+    let encountered_trees = phase("solve", || {
+        lines
+            .into_iter()
+            .calculate_encountered_trees(movement_scheme)
+    });
 
     println!("Encountered trees: {}", encountered_trees);
+    Ok(())
 }
 
 impl<I> TobogganTrajectory for I
@@ -80,60 +72,31 @@ mod example_data {
     use super::MovementScheme;
     use super::TobogganTrajectory;
 
-    #[test]
-    fn without_wrapping() {
-        let input = vec![
-            String::from("............."),
-            String::from("............."),
-            String::from("......#......"),
-            String::from(".........#..."),
-            String::from("............#"),
-        ];
+    // Examples in `examples/day03` of the repository.
+    const MAP: &str = include_str!("../../../examples/day03/map.txt");
+    const WITH_WRAPPING: &str = include_str!("../../../examples/day03/with-wrapping.txt");
+    const WITHOUT_WRAPPING: &str = include_str!("../../../examples/day03/without-wrapping.txt");
+
+    fn encountered_trees(raw_map: &str) -> usize {
         let movement_scheme = MovementScheme { right: 3, down: 1 };
-        let encountered_trees = input
-            .into_iter()
-            .calculate_encountered_trees(movement_scheme);
+        raw_map
+            .lines()
+            .map(String::from)
+            .calculate_encountered_trees(movement_scheme)
+    }
 
-        assert_eq!(encountered_trees, 3);
+    #[test]
+    fn without_wrapping() {
+        assert_eq!(encountered_trees(WITHOUT_WRAPPING), 3);
     }
 
     #[test]
     fn with_wrapping() {
-        let input = vec![
-            String::from("......."),
-            String::from("...#..."),
-            String::from("......#"),
-            String::from("..#...."),
-            String::from(".....#."),
-        ];
-        let movement_scheme = MovementScheme { right: 3, down: 1 };
-        let encountered_trees = input
-            .into_iter()
-            .calculate_encountered_trees(movement_scheme);
-
-        assert_eq!(encountered_trees, 4);
+        assert_eq!(encountered_trees(WITH_WRAPPING), 4);
     }
 
     #[test]
     fn example_data() {
-        let input = vec![
-            String::from("..##.........##.........##.........##.........##.........##......."),
-            String::from("#...#...#..#...#...#..#...#...#..#...#...#..#...#...#..#...#...#.."),
-            String::from(".#....#..#..#....#..#..#....#..#..#....#..#..#....#..#..#....#..#."),
-            String::from("..#.#...#.#..#.#...#.#..#.#...#.#..#.#...#.#..#.#...#.#..#.#...#.#"),
-            String::from(".#...##..#..#...##..#..#...##..#..#...##..#..#...##..#..#...##..#."),
-            String::from("..#.##.......#.##.......#.##.......#.##.......#.##.......#.##....."),
-            String::from(".#.#.#....#.#.#.#....#.#.#.#....#.#.#.#....#.#.#.#....#.#.#.#....#"),
-            String::from(".#........#.#........#.#........#.#........#.#........#.#........#"),
-            String::from("#.##...#...#.##...#...#.##...#...#.##...#...#.##...#...#.##...#..."),
-            String::from("#...##....##...##....##...##....##...##....##...##....##...##....#"),
-            String::from(".#..#...#.#.#..#...#.#.#..#...#.#.#..#...#.#.#..#...#.#.#..#...#.#"),
-        ];
-        let movement_scheme = MovementScheme { right: 3, down: 1 };
-        let encountered_trees = input
-            .into_iter()
-            .calculate_encountered_trees(movement_scheme);
-
-        assert_eq!(encountered_trees, 7);
+        assert_eq!(encountered_trees(MAP), 7);
     }
 }
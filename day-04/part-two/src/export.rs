@@ -0,0 +1,238 @@
+use crate::{EyeColor, HairColor, Height, Passport, Profile, Record};
+use serde_json::{json, Map, Value};
+use std::fmt;
+
+/// Value of a single field converted into its type. Fields which can't be
+/// converted are kept as text.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    Year(u16),
+    Height(Height),
+    HairColor(HairColor),
+    EyeColor(EyeColor),
+    PassportId([u8; 9]),
+    Text(String),
+}
+
+/// Format of the exported passports.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    /// One JSON object per line.
+    JsonLines,
+    /// One column per known field.
+    Csv,
+    /// Batch file format with sorted keys.
+    Text,
+}
+
+impl FieldValue {
+    pub fn parse(key: &str, value: &str) -> Self {
+        let typed_value = match key {
+            Passport::BYR | Passport::IYR | Passport::EYR => {
+                Passport::parse_year(value).map(FieldValue::Year)
+            }
+            Passport::HGT => Passport::parse_height(value).map(FieldValue::Height),
+            Passport::HCL => Passport::parse_hair_color(value).map(FieldValue::HairColor),
+            Passport::ECL => Passport::parse_eye_color(value).map(FieldValue::EyeColor),
+            Passport::PID => Passport::parse_passport_id(value).map(FieldValue::PassportId),
+            _ => Ok(FieldValue::Text(value.to_string())),
+        };
+        typed_value.unwrap_or_else(|_| FieldValue::Text(value.to_string()))
+    }
+
+    fn to_json(&self) -> Value {
+        match self {
+            FieldValue::Year(year) => json!(year),
+            FieldValue::Height(Height::Cm(value)) => json!({ "value": value, "unit": "cm" }),
+            FieldValue::Height(Height::In(value)) => json!({ "value": value, "unit": "in" }),
+            field_value => json!(field_value.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FieldValue::Year(year) => write!(f, "{}", year),
+            FieldValue::Height(height) => write!(f, "{}", height),
+            FieldValue::HairColor(hair_color) => write!(f, "{}", hair_color),
+            FieldValue::EyeColor(eye_color) => write!(f, "{}", eye_color),
+            FieldValue::PassportId(digits) => {
+                for digit in digits {
+                    write!(f, "{}", digit)?;
+                }
+                Ok(())
+            }
+            FieldValue::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+/// Writes all records in the given format. Validity of every passport is
+/// checked with `profile`.
+pub fn export(records: &[Record], profile: &Profile, format: ExportFormat) -> String {
+    match format {
+        ExportFormat::JsonLines => export_json_lines(records, profile),
+        ExportFormat::Csv => export_csv(records, profile),
+        ExportFormat::Text => export_text(records),
+    }
+}
+
+fn export_json_lines(records: &[Record], profile: &Profile) -> String {
+    let mut output = String::new();
+    for record in records {
+        let fields = record
+            .passport
+            .fields()
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), FieldValue::parse(key, value).to_json()))
+            .collect::<Map<String, Value>>();
+        let passport = json!({
+            "passport": record.index,
+            "valid": record.is_valid(profile),
+            "fields": fields,
+        });
+        output.push_str(&passport.to_string());
+        output.push('\n');
+    }
+    output
+}
+
+fn export_csv(records: &[Record], profile: &Profile) -> String {
+    let mut output = format!("passport,valid,{}\n", Passport::KEYS.join(","));
+    for record in records {
+        let mut row = vec![
+            record.index.to_string(),
+            record.is_valid(profile).to_string(),
+        ];
+        for key in Passport::KEYS {
+            let value = record
+                .passport
+                .get(key)
+                .map(|value| FieldValue::parse(key, value).to_string())
+                .unwrap_or_default();
+            row.push(csv_escape(&value));
+        }
+        output.push_str(&row.join(","));
+        output.push('\n');
+    }
+    output
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains(['\r', '\n', ',', '"']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn export_text(records: &[Record]) -> String {
+    records
+        .iter()
+        .map(|record| {
+            let fields = record
+                .passport
+                .fields()
+                .into_iter()
+                .map(|(key, value)| format!("{}:{}", key, value))
+                .collect::<Vec<String>>();
+            fields.join(" ") + "\n"
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::{export, ExportFormat, FieldValue};
+    use crate::{parse_records, EyeColor, Height, Profile, Record};
+    use serde_json::Value;
+
+    fn records() -> Vec<Record> {
+        parse_records(
+            "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980\n\
+             hcl:#623a2f\n\
+             \n\
+             hgt:59cm ecl:zzz eyr:2038 hcl:74454a,\"x\" iyr:2023 byr:2007 cid:7\n",
+        )
+    }
+
+    #[test]
+    fn typed_values() {
+        assert_eq!(FieldValue::parse("byr", "1980"), FieldValue::Year(1980));
+        assert_eq!(
+            FieldValue::parse("hgt", "74in"),
+            FieldValue::Height(Height::In(74))
+        );
+        assert_eq!(
+            FieldValue::parse("ecl", "grn"),
+            FieldValue::EyeColor(EyeColor::Green)
+        );
+        assert_eq!(
+            FieldValue::parse("ecl", "zzz"),
+            FieldValue::Text(String::from("zzz"))
+        );
+        assert_eq!(
+            FieldValue::parse("pid", "087499704").to_string(),
+            "087499704"
+        );
+    }
+
+    #[test]
+    fn json_lines() {
+        let output = export(&records(), Profile::aoc(), ExportFormat::JsonLines);
+        let lines = output
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap())
+            .collect::<Vec<Value>>();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["valid"], true);
+        assert_eq!(lines[0]["fields"]["byr"], 1980);
+        assert_eq!(lines[0]["fields"]["hgt"]["value"], 74);
+        assert_eq!(lines[0]["fields"]["hgt"]["unit"], "in");
+        assert_eq!(lines[0]["fields"]["pid"], "087499704");
+        assert_eq!(lines[1]["valid"], false);
+        assert_eq!(lines[1]["fields"]["byr"], 2007);
+        assert_eq!(lines[1]["fields"]["ecl"], "zzz");
+    }
+
+    #[test]
+    fn csv() {
+        let output = export(&records(), Profile::aoc(), ExportFormat::Csv);
+
+        assert_eq!(
+            output,
+            "passport,valid,byr,iyr,eyr,hgt,hcl,ecl,pid,cid\n\
+             1,true,1980,2012,2030,74in,#623a2f,grn,087499704,\n\
+             2,false,2007,2023,2038,59cm,\"74454a,\"\"x\"\"\",zzz,,7\n"
+        );
+    }
+
+    #[test]
+    fn csv_line_breaks() {
+        // Values with line breaks can't come from a batch file, but the
+        // passport can be built in any other way.
+        let mut records = records();
+        records[1].passport = [("cid", "7\r"), ("hcl", "a\r\nb"), ("ecl", "\rzzz")]
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        let output = export(&records, Profile::aoc(), ExportFormat::Csv);
+
+        assert!(output.ends_with("\n2,false,,,,,\"a\r\nb\",\"\rzzz\",,\"7\r\"\n"));
+    }
+
+    #[test]
+    fn text() {
+        let output = export(&records(), Profile::aoc(), ExportFormat::Text);
+
+        assert_eq!(
+            output,
+            "byr:1980 ecl:grn eyr:2030 hcl:#623a2f hgt:74in iyr:2012 pid:087499704\n\
+             \n\
+             byr:2007 cid:7 ecl:zzz eyr:2038 hcl:74454a,\"x\" hgt:59cm iyr:2023\n"
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{Profile, RecordReader};
+use std::io::{self, BufRead};
+
+/// How strictly passports are validated.
+#[derive(Debug, Clone)]
+pub enum Level {
+    /// Only required fields must be present, as in the first part of the
+    /// puzzle.
+    Presence,
+    /// Values of the fields are validated too, as in the second part of the
+    /// puzzle.
+    Values,
+    /// Rules are loaded from a profile file.
+    Custom(Profile),
+}
+
+impl Level {
+    pub fn profile(&self) -> &Profile {
+        match self {
+            Level::Presence => Profile::presence(),
+            Level::Values => Profile::aoc(),
+            Level::Custom(profile) => profile,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Level::Presence => "presence",
+            Level::Values => "values",
+            Level::Custom(profile) => &profile.name,
+        }
+    }
+}
+
+/// Counts valid passports at every level in a single pass over the reader.
+/// Counts are in the same order as `levels`.
+pub fn count_valid_passports_by_level<R: BufRead>(
+    reader: R,
+    levels: &[Level],
+) -> io::Result<Vec<usize>> {
+    let mut valid_passports = vec![0; levels.len()];
+    for record in RecordReader::new(reader) {
+        let record = record?;
+        for (count, level) in valid_passports.iter_mut().zip(levels) {
+            if record.is_valid(level.profile()) {
+                *count += 1;
+            }
+        }
+    }
+    Ok(valid_passports)
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::{count_valid_passports_by_level, Level};
+    use crate::Profile;
+
+    #[test]
+    fn both_parts() {
+        // Example from the first part of the puzzle.
+        let lines = [
+            "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd\n",
+            "byr:1937 iyr:2017 cid:147 hgt:183cm\n",
+            "\n",
+            "iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884\n",
+            "hcl:#cfa07d byr:1929\n",
+            "\n",
+            "hcl:#ae17e1 iyr:2013\n",
+            "eyr:2024\n",
+            "ecl:brn pid:760753108 byr:1931\n",
+            "hgt:179cm\n",
+            "\n",
+            "hcl:#cfa07d eyr:2025 pid:166559648\n",
+            "iyr:2011 ecl:brn hgt:59in\n",
+            "\n",
+            "byr:2010 iyr:2011 eyr:2025 hgt:190in hcl:123abc ecl:brn pid:1\n",
+        ];
+        let one_string = lines.concat();
+        let custom_profile = Profile::from_toml(
+            "name = \"custom\"\n[[field]]\nkey = \"hgt\"\nrequired = true\ntype = \"any\"\n",
+        )
+        .unwrap();
+        let levels = [
+            Level::Presence,
+            Level::Values,
+            Level::Custom(custom_profile),
+        ];
+        let valid_passports =
+            count_valid_passports_by_level(one_string.as_bytes(), &levels).unwrap();
+
+        assert_eq!(valid_passports, vec![3, 2, 4]);
+        assert_eq!(
+            levels.iter().map(Level::name).collect::<Vec<&str>>(),
+            vec!["presence", "values", "custom"]
+        );
+    }
+}
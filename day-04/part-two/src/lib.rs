@@ -0,0 +1,78 @@
+mod export;
+mod level;
+mod passport;
+mod profile;
+mod record;
+mod repair;
+mod report;
+mod stats;
+
+use std::io::{self, BufRead};
+
+pub use export::{export, ExportFormat, FieldValue};
+pub use level::{count_valid_passports_by_level, Level};
+pub use passport::{
+    EyeColor, FieldError, FieldErrorKind, HairColor, Height, InvalidPassport, PartialPassport,
+    Passport, RawPassport,
+};
+pub use profile::Profile;
+pub use record::{parse_records, Record, RecordReader, TokenError, TokenErrorKind};
+pub use repair::{repair, suggest_repairs, Repair, RepairKind};
+pub use report::{diagnose, json_report, text_report, Diagnostic};
+pub use stats::{statistics, Statistics};
+
+pub fn count_valid_passports(raw_string: String) -> usize {
+    count_valid_passports_with_profile(&raw_string, Profile::aoc())
+}
+
+pub fn count_valid_passports_with_profile(raw_string: &str, profile: &Profile) -> usize {
+    parse_records(raw_string)
+        .into_iter()
+        .filter(|record| record.is_valid(profile))
+        .count()
+}
+
+/// Counts valid passports read one at a time from the reader, without
+/// loading the whole batch file into memory.
+pub fn count_valid_passports_from_reader<R: BufRead>(
+    reader: R,
+    profile: &Profile,
+) -> io::Result<usize> {
+    let mut valid_passports = 0;
+    for record in RecordReader::new(reader) {
+        let record = record?;
+        if record.is_valid(profile) {
+            valid_passports += 1;
+        }
+    }
+    Ok(valid_passports)
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::{count_valid_passports, count_valid_passports_from_reader, Profile};
+
+    // Examples in `examples/day04` of the repository.
+    const INVALID_PASSPORTS: &str = include_str!("../../../examples/day04/invalid-passports.txt");
+    const VALID_PASSPORTS: &str = include_str!("../../../examples/day04/valid-passports.txt");
+
+    #[test]
+    fn invalid_data() {
+        let one_string = INVALID_PASSPORTS;
+        let streamed_passports =
+            count_valid_passports_from_reader(one_string.as_bytes(), Profile::aoc()).unwrap();
+        let valid_passports = count_valid_passports(one_string.to_string());
+        assert_eq!(valid_passports, 0);
+        assert_eq!(streamed_passports, valid_passports);
+    }
+
+    #[test]
+    fn valid_data() {
+        let one_string = VALID_PASSPORTS;
+        let streamed_passports =
+            count_valid_passports_from_reader(one_string.as_bytes(), Profile::aoc()).unwrap();
+        let valid_passports = count_valid_passports(one_string.to_string());
+        assert_eq!(valid_passports, 4);
+        assert_eq!(streamed_passports, valid_passports);
+    }
+}
@@ -1,286 +1,187 @@
-use regex::Regex;
-use std::collections::HashMap;
-use std::env;
-use std::path::Path;
+use aoc_common::{phase, Args, Error, InputError};
+use part_two::{
+    count_valid_passports_by_level, diagnose, export, json_report, parse_records, repair,
+    text_report, ExportFormat, Level, Profile, Record, RecordReader, Statistics,
+};
+use std::process::ExitCode;
+
+const USAGE: &str = "Program must be executed with arguments: [input] \
+                     [--report text|json|stats | --export jsonl|csv|text | --repair output_file] \
+                     [--level presence|values|custom] [--profile profile_file]";
+
+fn main() -> ExitCode {
+    aoc_common::run(solve)
+}
 
-struct Passport(HashMap<String, String>);
+fn solve() -> Result<(), Error> {
+    let args = Args::from_env(USAGE, ..)?;
+    let usage_error = || Error::Usage(USAGE.to_string());
+
+    // Arguments after the input are optional pairs of option and its value.
+    // `--report` prints diagnostics of every passport, or statistics of the
+    // whole batch, and `--export` prints every passport, instead of the
+    // number of valid passports. `--repair` writes the batch file with
+    // suggested fixes into the output file and prints every fix. `--level`
+    // selects how strictly passports are validated, `custom` level uses rules
+    // from `--profile`. Without `--level` valid passports are counted at
+    // every level.
+    let mut report_format = None;
+    let mut export_format = None;
+    let mut repair_file_name = None;
+    let mut level_name = None;
+    let mut profile_file_name = None;
+    for option in args.rest.chunks(2) {
+        match option {
+            [name, format]
+                if name == "--report"
+                    && (format == "text" || format == "json" || format == "stats") =>
+            {
+                report_format = Some(format)
+            }
+            [name, format] if name == "--export" => {
+                export_format = match format.as_str() {
+                    "jsonl" => Some(ExportFormat::JsonLines),
+                    "csv" => Some(ExportFormat::Csv),
+                    "text" => Some(ExportFormat::Text),
+                    _ => return Err(usage_error()),
+                }
+            }
+            [name, level]
+                if name == "--level"
+                    && (level == "presence" || level == "values" || level == "custom") =>
+            {
+                level_name = Some(level)
+            }
+            [name, file_name] if name == "--repair" => repair_file_name = Some(file_name),
+            [name, file_name] if name == "--profile" => profile_file_name = Some(file_name),
+            _ => return Err(usage_error()),
+        }
+    }
 
-fn main() {
-    let args = env::args().collect::<Vec<String>>();
-    if args.len() != 2 {
-        eprintln!("Program must be executed with one argument: [file_name]");
-        return;
+    let outputs = [
+        report_format.is_some(),
+        export_format.is_some(),
+        repair_file_name.is_some(),
+    ];
+    if outputs.into_iter().filter(|output| *output).count() > 1 {
+        return Err(usage_error());
     }
 
-    // First argument is name of binary file.
-    // Usefully is second argument which is `file name` with data.
-    let file_name = match args.get(1) {
-        Some(name) => name,
-        None => {
-            eprintln!("unable to get file name");
-            return;
+    let custom_level = match profile_file_name {
+        Some(profile_file_name) => {
+            let raw_profile = std::fs::read_to_string(profile_file_name)
+                .map_err(|e| Error::io("unable to read profile from file", e))?;
+            let profile = Profile::from_toml(&raw_profile).map_err(Error::Parse)?;
+            Some(Level::Custom(profile))
         }
+        None => None,
     };
-    let path = Path::new(file_name);
-    let raw_data_file = match std::fs::read_to_string(path) {
-        Ok(data) => data,
-        Err(e) => {
-            eprintln!("unable to read data from file, {}", e);
-            return;
+    let levels = match (level_name.map(String::as_str), custom_level) {
+        (Some("presence"), None) => vec![Level::Presence],
+        (Some("values"), None) => vec![Level::Values],
+        (Some("custom"), Some(custom_level)) => vec![custom_level],
+        // Profile alone is the same as the `custom` level.
+        (None, Some(custom_level)) if outputs.contains(&true) => {
+            vec![custom_level]
         }
-    };
-
-    let valid_passports = count_valid_passports(raw_data_file);
-    println!("Valid passports: {}", valid_passports);
-}
-
-fn count_valid_passports(raw_string: String) -> usize {
-    raw_string
-        .split("\n\n")
-        .map(|raw_data| {
-            raw_data
-                .split_ascii_whitespace()
-                .map(|key_value| {
-                    let mut pair_data = key_value.split(':');
-                    let key = pair_data.next().unwrap().to_string();
-                    let value = pair_data.next().unwrap().to_string();
-                    (key, value)
-                })
-                .collect::<Passport>()
-        })
-        .filter(Passport::is_valid)
-        .count()
-}
-
-impl Passport {
-    /// Key for `birth year`.
-    const BYR: &'static str = "byr";
-    /// Key for `issue year`.
-    const IYR: &'static str = "iyr";
-    /// Key for `expiration year`.
-    const EYR: &'static str = "eyr";
-    /// Key for `height`.
-    const HGT: &'static str = "hgt";
-    /// Key for `hair color`.
-    const HCL: &'static str = "hcl";
-    /// Key for `eye color`.
-    const ECL: &'static str = "ecl";
-    /// Key for `passport ID`.
-    const PID: &'static str = "pid";
-    /// Key for `country ID`.
-    #[allow(dead_code)]
-    const CID: &'static str = "cid";
-
-    const REQUIRED_KEYS: [&'static str; 7] = [
-        Self::BYR,
-        Self::IYR,
-        Self::EYR,
-        Self::HGT,
-        Self::HCL,
-        Self::ECL,
-        Self::PID,
-        // Field `cid` is not necessary - it's optional.
-    ];
-
-    pub fn is_valid(&self) -> bool {
-        self.contains_required_keys()
-            && self.is_valid_birth_year()
-            && self.is_valid_issue_year()
-            && self.is_valid_expiration_year()
-            && self.is_valid_height()
-            && self.is_valid_hair_color()
-            && self.is_valid_eye_color()
-            && self.is_valid_passport_id()
-    }
-
-    fn contains_required_keys(&self) -> bool {
-        Self::REQUIRED_KEYS
-            .into_iter()
-            .all(|key| self.0.contains_key(key))
-    }
-
-    fn is_valid_birth_year(&self) -> bool {
-        const MIN_VALID_YEAR: u16 = 1920;
-        const MAX_VALID_YEAR: u16 = 2002;
-
-        let birth_year = match self.0.get(Self::BYR) {
-            Some(val) => val.parse::<u16>(),
-            None => return false,
-        };
-
-        // We can use:
-        // `matches!(birth_year, Ok(year) if (MIN_VALID_YEAR..=MAX_VALID_YEAR).contains(&year))`
-        // but `cargo fmt` does not format correctly long macro.
-        #[allow(clippy::match_like_matches_macro)]
-        match birth_year {
-            Ok(year) if (MIN_VALID_YEAR..=MAX_VALID_YEAR).contains(&year) => true,
-            _ => false,
+        (None, custom_level) => {
+            let mut levels = vec![Level::Presence, Level::Values];
+            levels.extend(custom_level);
+            levels
         }
-    }
-
-    fn is_valid_issue_year(&self) -> bool {
-        const MIN_VALID_YEAR: u16 = 2010;
-        const MAX_VALID_YEAR: u16 = 2020;
-
-        let issue_year = match self.0.get(Self::IYR) {
-            Some(val) => val.parse::<u16>(),
-            None => return false,
-        };
-
-        // We can use:
-        // `matches!(issue_year, Ok(year) if (MIN_VALID_YEAR..=MAX_VALID_YEAR).contains(&year))`
-        // but `cargo fmt` does not format correctly long macro.
-        #[allow(clippy::match_like_matches_macro)]
-        match issue_year {
-            Ok(year) if (MIN_VALID_YEAR..=MAX_VALID_YEAR).contains(&year) => true,
-            _ => false,
+        (Some("custom"), None) => {
+            return Err(Error::Usage(String::from(
+                "`--level custom` requires a profile file given with `--profile`",
+            )))
         }
-    }
-
-    fn is_valid_expiration_year(&self) -> bool {
-        const MIN_VALID_YEAR: u16 = 2020;
-        const MAX_VALID_YEAR: u16 = 2030;
-
-        let expiration_year = match self.0.get(Self::EYR) {
-            Some(val) => val.parse::<u16>(),
-            None => return false,
-        };
-
-        // We can use:
-        // `matches!(expiration_year, Ok(year) if (MIN_VALID_YEAR..=MAX_VALID_YEAR).contains(&year))`
-        // but `cargo fmt` does not format correctly long macro.
-        #[allow(clippy::match_like_matches_macro)]
-        match expiration_year {
-            Ok(year) if (MIN_VALID_YEAR..=MAX_VALID_YEAR).contains(&year) => true,
-            _ => false,
+        _ => {
+            return Err(Error::Usage(String::from(
+                "`--profile` is only valid with `--level custom`",
+            )))
         }
-    }
-
-    fn is_valid_height(&self) -> bool {
-        const MIN_CM_HEIGHT: u8 = 150;
-        const MAX_CM_HEIGHT: u8 = 193;
-        const MIN_IN_HEIGHT: u8 = 59;
-        const MAX_IN_HEIGHT: u8 = 76;
-
-        let height = match self.0.get(Self::HGT) {
-            Some(val) => val,
-            None => return false,
-        };
-        if let Some(char_position) = height.find("cm") {
-            match height[0..char_position].parse::<u8>() {
-                Ok(value) if (MIN_CM_HEIGHT..=MAX_CM_HEIGHT).contains(&value) => return true,
-                _ => return false,
+    };
+    // Errors of the streaming reader are reported like errors of any other
+    // input.
+    let read_error = |error| {
+        Error::from(InputError::Io {
+            source: args.input.to_string(),
+            error,
+        })
+    };
+    // Reports and exports use the strictest level, only counting can be done
+    // at many levels at once.
+    // This `unwrap()` is safe because there is always at least one level.
+    let profile = levels.last().unwrap().profile();
+
+    if !outputs.contains(&true) || report_format.is_some_and(|format| format == "stats") {
+        // Only the number of valid passports or statistics are needed, so
+        // the batch file is read one passport at a time.
+        let reader = args.input.reader()?;
+        if report_format.is_none() {
+            // Passports are parsed and validated one at a time, so there is
+            // no separate parsing phase.
+            let valid_passports = phase("solve", || {
+                count_valid_passports_by_level(reader, &levels).map_err(read_error)
+            })?;
+            if let [valid_passports] = valid_passports[..] {
+                println!("Valid passports: {}", valid_passports);
+                return Ok(());
             }
-        }
-        if let Some(char_position) = height.find("in") {
-            match height[0..char_position].parse::<u8>() {
-                Ok(value) if (MIN_IN_HEIGHT..=MAX_IN_HEIGHT).contains(&value) => return true,
-                _ => return false,
+            for (level, valid_passports) in levels.iter().zip(valid_passports) {
+                println!("Valid passports ({}): {}", level.name(), valid_passports);
             }
+            return Ok(());
         }
-        false
-    }
 
-    fn is_valid_hair_color(&self) -> bool {
-        // Regex: a `#` followed by exactly six characters `0-9` or `a-f`.
-        let re = Regex::new("#([0-9a-f]){6}$").unwrap();
-        let hair_color = match self.0.get(Self::HCL) {
-            Some(val) => val,
-            None => return false,
-        };
-        re.is_match(hair_color)
+        let mut statistics = Statistics::new();
+        for record in RecordReader::new(reader) {
+            statistics.add(&record.map_err(read_error)?, profile);
+        }
+        print!("{}", statistics);
+        return Ok(());
     }
 
-    fn is_valid_eye_color(&self) -> bool {
-        const AVAILABLE_EYE_COLOR: [&str; 7] = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+    let raw_data_file = args.input.text()?;
 
-        let eye_color = match self.0.get(Self::ECL) {
-            Some(val) => val,
-            None => return false,
-        };
-        AVAILABLE_EYE_COLOR
-            .into_iter()
-            .any(|color| eye_color.contains(color))
+    if let Some(repair_file_name) = repair_file_name {
+        let (repaired_data, change_log) = repair(&raw_data_file, profile);
+        std::fs::write(repair_file_name, &repaired_data)
+            .map_err(|e| Error::io("unable to write repaired data to file", e))?;
+        // Fixes are only suggestions, so every one of them is listed.
+        for repair in change_log.iter() {
+            println!("{}", repair);
+        }
+        println!(
+            "Fixes: {}, valid passports: {} before, {} after",
+            change_log.len(),
+            count_valid_records(&parse_records(&raw_data_file), profile),
+            // Counted in the written file, so the count describes it exactly.
+            count_valid_records(&parse_records(&repaired_data), profile)
+        );
+        return Ok(());
     }
 
-    fn is_valid_passport_id(&self) -> bool {
-        // Regex: a nine-digit number, including leading zeroes.
-        let re = Regex::new("^[0-9]{9}$").unwrap();
-        let passport_id = match self.0.get(Self::PID) {
-            Some(val) => val,
-            None => return false,
-        };
-        re.is_match(passport_id)
+    if let Some(export_format) = export_format {
+        let records = parse_records(&raw_data_file);
+        print!("{}", export(&records, profile, export_format));
+        return Ok(());
     }
-}
 
-impl FromIterator<(String, String)> for Passport {
-    fn from_iter<T>(iter: T) -> Self
-    where
-        T: IntoIterator<Item = (String, String)>,
-    {
-        let mut hash_map = HashMap::new();
-        for (key, value) in iter {
-            hash_map.insert(key, value);
+    if let Some(report_format) = report_format {
+        let records = parse_records(&raw_data_file);
+        let diagnostics = diagnose(&records, profile);
+        if report_format == "json" {
+            println!("{}", json_report(&diagnostics));
+        } else {
+            print!("{}", text_report(&diagnostics));
         }
-        Passport(hash_map)
     }
+    Ok(())
 }
 
-#[cfg(test)]
-mod example_data {
-    use super::count_valid_passports;
-
-    #[test]
-    fn invalid_data() {
-        // Simulates data saved in file.
-        // Each line is ended with newline ('\n').
-        let lines = vec![
-            "eyr:1972 cid:100\n",
-            "hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926\n",
-            "\n",
-            "iyr:2019\n",
-            "hcl:#602927 eyr:1967 hgt:170cm\n",
-            "ecl:grn pid:012533040 byr:1946\n",
-            "\n",
-            "hcl:dab227 iyr:2012\n",
-            "ecl:brn hgt:182cm pid:021572410 eyr:2020 byr:1992 cid:277\n",
-            "\n",
-            "hgt:59cm ecl:zzz\n",
-            "eyr:2038 hcl:74454a iyr:2023\n",
-            "pid:3556412378 byr:2007",
-        ];
-        let mut one_string = String::new();
-        for line in lines {
-            one_string.push_str(line);
-        }
-        let valid_passports = count_valid_passports(one_string);
-        assert_eq!(valid_passports, 0);
-    }
-
-    #[test]
-    fn valid_data() {
-        // Simulates data saved in file.
-        // Each line is ended with newline ('\n').
-        let lines = vec![
-            "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980\n",
-            "hcl:#623a2f\n",
-            "\n",
-            "eyr:2029 ecl:blu cid:129 byr:1989\n",
-            "iyr:2014 pid:896056539 hcl:#a97842 hgt:165cm\n",
-            "\n",
-            "hcl:#888785\n",
-            "hgt:164cm byr:2001 iyr:2015 cid:88\n",
-            "pid:545766238 ecl:hzl\n",
-            "eyr:2022\n",
-            "\n",
-            "iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719",
-        ];
-        let mut one_string = String::new();
-        for line in lines {
-            one_string.push_str(line);
-        }
-        let valid_passports = count_valid_passports(one_string);
-        assert_eq!(valid_passports, 4);
-    }
+fn count_valid_records(records: &[Record], profile: &Profile) -> usize {
+    records
+        .iter()
+        .filter(|record| record.is_valid(profile))
+        .count()
 }
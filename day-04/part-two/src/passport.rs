@@ -0,0 +1,659 @@
+use crate::Profile;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Fields of a single passport exactly as they were written in the batch
+/// file, before any validation.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RawPassport(HashMap<String, String>);
+
+/// Passport with every required field of the puzzle converted into its
+/// type. It can be built only from a passport accepted by a profile whose
+/// values also fit the types, see `Passport::from_raw`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Passport {
+    pub birth_year: u16,
+    pub issue_year: u16,
+    pub expiration_year: u16,
+    pub height: Height,
+    pub hair_color: HairColor,
+    pub eye_color: EyeColor,
+    /// Nine digits, including leading zeroes.
+    pub passport_id: [u8; 9],
+    /// Country ID is not validated - it's optional.
+    pub country_id: Option<String>,
+}
+
+/// Typed view of any passport, also of an invalid one, e.g. for statistics
+/// of the batch. A field is `None` if it's missing, or if its value doesn't
+/// fit the type.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PartialPassport {
+    pub birth_year: Option<u16>,
+    pub issue_year: Option<u16>,
+    pub expiration_year: Option<u16>,
+    pub height: Option<Height>,
+    pub hair_color: Option<HairColor>,
+    pub eye_color: Option<EyeColor>,
+    pub passport_id: Option<[u8; 9]>,
+    pub country_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Height {
+    Cm(u8),
+    In(u8),
+}
+
+/// Hair color as red, green and blue components.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HairColor(pub [u8; 3]);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EyeColor {
+    Amber,
+    Blue,
+    Brown,
+    Gray,
+    Green,
+    Hazel,
+    Other,
+}
+
+/// Problem with a single field of the passport.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldError {
+    pub key: String,
+    pub kind: FieldErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldErrorKind {
+    Missing,
+    NotANumber(String),
+    /// `unit` is empty for values without unit, e.g. years.
+    OutOfRange {
+        value: u32,
+        min: u32,
+        max: u32,
+        unit: String,
+    },
+    UnknownUnit(String),
+    InvalidFormat(String),
+    UnknownValue(String),
+    DigitCount {
+        digits: usize,
+        expected: usize,
+    },
+}
+
+/// Passport which can't be converted into `Passport`, with every problem
+/// found. Fields which fit their types are converted anyway.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidPassport {
+    pub passport: PartialPassport,
+    pub errors: Vec<FieldError>,
+}
+
+impl RawPassport {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.0.contains_key(key)
+    }
+
+    /// All fields of the passport, sorted by key.
+    pub fn fields(&self) -> Vec<(&str, &str)> {
+        let mut fields = self
+            .0
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect::<Vec<(&str, &str)>>();
+        fields.sort_unstable();
+        fields
+    }
+}
+
+impl FromIterator<(String, String)> for RawPassport {
+    fn from_iter<T>(iter: T) -> Self
+    where
+        T: IntoIterator<Item = (String, String)>,
+    {
+        let mut hash_map = HashMap::new();
+        for (key, value) in iter {
+            hash_map.insert(key, value);
+        }
+        RawPassport(hash_map)
+    }
+}
+
+impl Passport {
+    /// Key for `birth year`.
+    pub const BYR: &'static str = "byr";
+    /// Key for `issue year`.
+    pub const IYR: &'static str = "iyr";
+    /// Key for `expiration year`.
+    pub const EYR: &'static str = "eyr";
+    /// Key for `height`.
+    pub const HGT: &'static str = "hgt";
+    /// Key for `hair color`.
+    pub const HCL: &'static str = "hcl";
+    /// Key for `eye color`.
+    pub const ECL: &'static str = "ecl";
+    /// Key for `passport ID`.
+    pub const PID: &'static str = "pid";
+    /// Key for `country ID`.
+    pub const CID: &'static str = "cid";
+
+    pub const REQUIRED_KEYS: [&'static str; 7] = [
+        Self::BYR,
+        Self::IYR,
+        Self::EYR,
+        Self::HGT,
+        Self::HCL,
+        Self::ECL,
+        Self::PID,
+        // Field `cid` is not necessary - it's optional.
+    ];
+
+    /// Keys of all fields known from the puzzle, also the optional ones.
+    pub const KEYS: [&'static str; 8] = [
+        Self::BYR,
+        Self::IYR,
+        Self::EYR,
+        Self::HGT,
+        Self::HCL,
+        Self::ECL,
+        Self::PID,
+        Self::CID,
+    ];
+
+    /// Checks the passport with `profile` and converts its fields into their
+    /// types. All fields are checked, so every problem with the passport is
+    /// reported - not only the first one.
+    ///
+    /// Every required field must be present and fit its type, also when the
+    /// profile doesn't check it, e.g. the `presence` profile accepts `hgt:59`,
+    /// which has no unit.
+    pub fn from_raw(
+        raw_passport: &RawPassport,
+        profile: &Profile,
+    ) -> Result<Self, InvalidPassport> {
+        let mut errors = profile.validate(raw_passport);
+        for key in Self::REQUIRED_KEYS {
+            if errors.iter().any(|error| error.key == key) {
+                continue;
+            }
+            let kind = match raw_passport
+                .get(key)
+                .map(|value| Self::check_type(key, value))
+            {
+                None => FieldErrorKind::Missing,
+                Some(Err(kind)) => kind,
+                Some(Ok(())) => continue,
+            };
+            errors.push(FieldError {
+                key: key.to_string(),
+                kind,
+            });
+        }
+
+        let passport = PartialPassport::from(raw_passport);
+        if !errors.is_empty() {
+            return Err(InvalidPassport { passport, errors });
+        }
+        match passport {
+            PartialPassport {
+                birth_year: Some(birth_year),
+                issue_year: Some(issue_year),
+                expiration_year: Some(expiration_year),
+                height: Some(height),
+                hair_color: Some(hair_color),
+                eye_color: Some(eye_color),
+                passport_id: Some(passport_id),
+                country_id,
+            } => Ok(Passport {
+                birth_year,
+                issue_year,
+                expiration_year,
+                height,
+                hair_color,
+                eye_color,
+                passport_id,
+                country_id,
+            }),
+            // Every required field was converted, errors are reported above.
+            _ => unreachable!("required fields of a passport without errors are typed"),
+        }
+    }
+
+    /// Checks if the value of the known field fits its type.
+    fn check_type(key: &str, value: &str) -> Result<(), FieldErrorKind> {
+        match key {
+            Self::BYR | Self::IYR | Self::EYR => Self::parse_year(value).map(drop),
+            Self::HGT => Self::parse_height(value).map(drop),
+            Self::HCL => Self::parse_hair_color(value).map(drop),
+            Self::ECL => Self::parse_eye_color(value).map(drop),
+            Self::PID => Self::parse_passport_id(value).map(drop),
+            _ => Ok(()),
+        }
+    }
+
+    // Errors of parsing describe values which don't fit the type, so they
+    // are reported also for fields the profile doesn't check.
+
+    pub(crate) fn parse_year(value: &str) -> Result<u16, FieldErrorKind> {
+        parse_digits(value)
+    }
+
+    pub(crate) fn parse_height(value: &str) -> Result<Height, FieldErrorKind> {
+        if let Some(number) = value.strip_suffix("cm") {
+            return parse_digits(number).map(Height::Cm);
+        }
+        if let Some(number) = value.strip_suffix("in") {
+            return parse_digits(number).map(Height::In);
+        }
+        Err(FieldErrorKind::UnknownUnit(value.to_string()))
+    }
+
+    pub(crate) fn parse_hair_color(value: &str) -> Result<HairColor, FieldErrorKind> {
+        // A `#` followed by exactly six characters `0-9` or `a-f`.
+        let hex = match value.strip_prefix('#') {
+            Some(hex)
+                if hex.len() == 6
+                    && hex
+                        .bytes()
+                        .all(|symbol| matches!(symbol, b'0'..=b'9' | b'a'..=b'f')) =>
+            {
+                hex
+            }
+            _ => return Err(FieldErrorKind::InvalidFormat(value.to_string())),
+        };
+
+        // This `unwrap()` is safe because we've checked the hex digits
+        // before.
+        let mut rgb = [0; 3];
+        for (i, component) in rgb.iter_mut().enumerate() {
+            *component = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+        }
+        Ok(HairColor(rgb))
+    }
+
+    pub(crate) fn parse_eye_color(value: &str) -> Result<EyeColor, FieldErrorKind> {
+        value.parse::<EyeColor>()
+    }
+
+    pub(crate) fn parse_passport_id(value: &str) -> Result<[u8; 9], FieldErrorKind> {
+        if !value.bytes().all(|symbol| symbol.is_ascii_digit()) {
+            return Err(FieldErrorKind::InvalidFormat(value.to_string()));
+        }
+        if value.len() != 9 {
+            return Err(FieldErrorKind::DigitCount {
+                digits: value.len(),
+                expected: 9,
+            });
+        }
+
+        let mut digits = [0; 9];
+        for (digit, symbol) in digits.iter_mut().zip(value.bytes()) {
+            *digit = symbol - b'0';
+        }
+        Ok(digits)
+    }
+}
+
+/// Passport checked with the `aoc` profile, the rules of the puzzle.
+impl TryFrom<&RawPassport> for Passport {
+    type Error = InvalidPassport;
+
+    fn try_from(raw_passport: &RawPassport) -> Result<Self, Self::Error> {
+        Passport::from_raw(raw_passport, Profile::aoc())
+    }
+}
+
+impl From<&RawPassport> for PartialPassport {
+    fn from(raw_passport: &RawPassport) -> Self {
+        let field = |key: &str| raw_passport.get(key);
+        PartialPassport {
+            birth_year: field(Passport::BYR).and_then(|value| Passport::parse_year(value).ok()),
+            issue_year: field(Passport::IYR).and_then(|value| Passport::parse_year(value).ok()),
+            expiration_year: field(Passport::EYR)
+                .and_then(|value| Passport::parse_year(value).ok()),
+            height: field(Passport::HGT).and_then(|value| Passport::parse_height(value).ok()),
+            hair_color: field(Passport::HCL)
+                .and_then(|value| Passport::parse_hair_color(value).ok()),
+            eye_color: field(Passport::ECL).and_then(|value| Passport::parse_eye_color(value).ok()),
+            passport_id: field(Passport::PID)
+                .and_then(|value| Passport::parse_passport_id(value).ok()),
+            country_id: field(Passport::CID).map(String::from),
+        }
+    }
+}
+
+/// Parses a number written only with digits. Unlike `str::parse` it
+/// doesn't accept a sign, e.g. `+1980`.
+pub(crate) fn parse_digits<T: FromStr>(value: &str) -> Result<T, FieldErrorKind> {
+    if value.is_empty() || !value.bytes().all(|symbol| symbol.is_ascii_digit()) {
+        return Err(FieldErrorKind::NotANumber(value.to_string()));
+    }
+    value
+        .parse::<T>()
+        .map_err(|_| FieldErrorKind::NotANumber(value.to_string()))
+}
+
+impl EyeColor {
+    pub const ALL: [EyeColor; 7] = [
+        EyeColor::Amber,
+        EyeColor::Blue,
+        EyeColor::Brown,
+        EyeColor::Gray,
+        EyeColor::Green,
+        EyeColor::Hazel,
+        EyeColor::Other,
+    ];
+
+    /// Code of the eye color used in the batch file.
+    pub fn code(&self) -> &'static str {
+        match self {
+            EyeColor::Amber => "amb",
+            EyeColor::Blue => "blu",
+            EyeColor::Brown => "brn",
+            EyeColor::Gray => "gry",
+            EyeColor::Green => "grn",
+            EyeColor::Hazel => "hzl",
+            EyeColor::Other => "oth",
+        }
+    }
+}
+
+impl FromStr for EyeColor {
+    type Err = FieldErrorKind;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        EyeColor::ALL
+            .into_iter()
+            .find(|color| color.code() == value)
+            .ok_or_else(|| FieldErrorKind::UnknownValue(value.to_string()))
+    }
+}
+
+impl fmt::Display for Height {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Height::Cm(value) => write!(f, "{}cm", value),
+            Height::In(value) => write!(f, "{}in", value),
+        }
+    }
+}
+
+impl fmt::Display for HairColor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let [red, green, blue] = self.0;
+        write!(f, "#{:02x}{:02x}{:02x}", red, green, blue)
+    }
+}
+
+impl fmt::Display for EyeColor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl fmt::Display for FieldErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FieldErrorKind::Missing => write!(f, "missing"),
+            FieldErrorKind::NotANumber(value) => write!(f, "'{}' is not a number", value),
+            FieldErrorKind::OutOfRange {
+                value,
+                min,
+                max: _,
+                unit,
+            } if value < min => write!(f, "{}{} below minimum {}{}", value, unit, min, unit),
+            FieldErrorKind::OutOfRange {
+                value, max, unit, ..
+            } => write!(f, "{}{} above maximum {}{}", value, unit, max, unit),
+            FieldErrorKind::UnknownUnit(value) => {
+                write!(f, "'{}' has no unit, expected cm or in", value)
+            }
+            FieldErrorKind::InvalidFormat(value) => write!(f, "'{}' has invalid format", value),
+            FieldErrorKind::UnknownValue(value) => {
+                write!(f, "'{}' is not an allowed value", value)
+            }
+            FieldErrorKind::DigitCount { digits, expected } => {
+                write!(f, "{} digits, expected {}", digits, expected)
+            }
+        }
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.kind)
+    }
+}
+
+impl fmt::Display for InvalidPassport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let errors = self
+            .errors
+            .iter()
+            .map(FieldError::to_string)
+            .collect::<Vec<String>>();
+        write!(f, "{}", errors.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::{
+        EyeColor, FieldError, FieldErrorKind, HairColor, Height, PartialPassport, Passport,
+        RawPassport,
+    };
+    use crate::Profile;
+
+    fn raw_passport(raw_data: &str) -> RawPassport {
+        raw_data
+            .split_ascii_whitespace()
+            .map(|key_value| {
+                let mut pair_data = key_value.split(':');
+                let key = pair_data.next().unwrap().to_string();
+                let value = pair_data.next().unwrap().to_string();
+                (key, value)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn typed_passport() {
+        let raw_passport = raw_passport(
+            "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980 hcl:#623a2f cid:129",
+        );
+        let passport = Passport::from_raw(&raw_passport, Profile::aoc()).unwrap();
+
+        assert_eq!(
+            passport,
+            Passport {
+                birth_year: 1980,
+                issue_year: 2012,
+                expiration_year: 2030,
+                height: Height::In(74),
+                hair_color: HairColor([0x62, 0x3a, 0x2f]),
+                eye_color: EyeColor::Green,
+                passport_id: [0, 8, 7, 4, 9, 9, 7, 0, 4],
+                country_id: Some(String::from("129")),
+            }
+        );
+        assert_eq!(Passport::try_from(&raw_passport), Ok(passport));
+    }
+
+    #[test]
+    fn selected_profile() {
+        let raw_passport =
+            raw_passport("byr:1850 iyr:2012 eyr:2030 hgt:1 hcl:#623a2f ecl:grn pid:NP1234");
+        let profile = Profile::from_toml(
+            "name = \"north pole\"\n[[field]]\nkey = \"pid\"\nrequired = true\n\
+             type = \"regex\"\npattern = \"NP[0-9]{4}\"\n",
+        )
+        .unwrap();
+        assert!(profile.is_valid(&raw_passport));
+
+        // Values accepted by the profile, which don't fit their types.
+        let invalid_passport = Passport::from_raw(&raw_passport, &profile).unwrap_err();
+        assert_eq!(
+            invalid_passport.errors,
+            vec![
+                FieldError {
+                    key: String::from(Passport::HGT),
+                    kind: FieldErrorKind::UnknownUnit(String::from("1")),
+                },
+                FieldError {
+                    key: String::from(Passport::PID),
+                    kind: FieldErrorKind::InvalidFormat(String::from("NP1234")),
+                },
+            ]
+        );
+        assert_eq!(
+            invalid_passport.passport,
+            PartialPassport {
+                birth_year: Some(1850),
+                issue_year: Some(2012),
+                expiration_year: Some(2030),
+                height: None,
+                hair_color: Some(HairColor([0x62, 0x3a, 0x2f])),
+                eye_color: Some(EyeColor::Green),
+                passport_id: None,
+                country_id: None,
+            }
+        );
+
+        // Errors of the profile aren't repeated as type errors.
+        let errors = Passport::from_raw(&raw_passport, Profile::aoc())
+            .unwrap_err()
+            .errors;
+        assert_eq!(
+            errors[0],
+            FieldError {
+                key: String::from(Passport::BYR),
+                kind: FieldErrorKind::OutOfRange {
+                    value: 1850,
+                    min: 1920,
+                    max: 2002,
+                    unit: String::new(),
+                },
+            }
+        );
+        assert_eq!(
+            errors
+                .iter()
+                .map(|error| error.key.as_str())
+                .collect::<Vec<&str>>(),
+            vec![Passport::BYR, Passport::HGT, Passport::PID]
+        );
+    }
+
+    #[test]
+    fn presence_profile() {
+        let raw_passport = raw_passport("byr:1937 iyr:2017 eyr:2020 hgt:183 hcl:#fffffd ecl:gry");
+        let errors = Passport::from_raw(&raw_passport, Profile::presence())
+            .unwrap_err()
+            .errors;
+        assert_eq!(
+            errors,
+            vec![
+                FieldError {
+                    key: String::from(Passport::PID),
+                    kind: FieldErrorKind::Missing,
+                },
+                FieldError {
+                    key: String::from(Passport::HGT),
+                    kind: FieldErrorKind::UnknownUnit(String::from("183")),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn all_errors() {
+        let raw_passport =
+            raw_passport("hgt:59cm ecl:zzz eyr:2038 hcl:74454a iyr:2023 pid:3556412378 byr:19x7");
+        let errors = Passport::from_raw(&raw_passport, Profile::aoc())
+            .unwrap_err()
+            .errors;
+
+        assert_eq!(
+            errors,
+            vec![
+                FieldError {
+                    key: String::from(Passport::BYR),
+                    kind: FieldErrorKind::NotANumber(String::from("19x7")),
+                },
+                FieldError {
+                    key: String::from(Passport::IYR),
+                    kind: FieldErrorKind::OutOfRange {
+                        value: 2023,
+                        min: 2010,
+                        max: 2020,
+                        unit: String::new(),
+                    },
+                },
+                FieldError {
+                    key: String::from(Passport::EYR),
+                    kind: FieldErrorKind::OutOfRange {
+                        value: 2038,
+                        min: 2020,
+                        max: 2030,
+                        unit: String::new(),
+                    },
+                },
+                FieldError {
+                    key: String::from(Passport::HGT),
+                    kind: FieldErrorKind::OutOfRange {
+                        value: 59,
+                        min: 150,
+                        max: 193,
+                        unit: String::from("cm"),
+                    },
+                },
+                FieldError {
+                    key: String::from(Passport::HCL),
+                    kind: FieldErrorKind::InvalidFormat(String::from("74454a")),
+                },
+                FieldError {
+                    key: String::from(Passport::ECL),
+                    kind: FieldErrorKind::UnknownValue(String::from("zzz")),
+                },
+                FieldError {
+                    key: String::from(Passport::PID),
+                    kind: FieldErrorKind::DigitCount {
+                        digits: 10,
+                        expected: 9,
+                    },
+                },
+            ]
+        );
+        assert_eq!(errors[3].to_string(), "hgt: 59cm below minimum 150cm");
+        assert_eq!(errors[6].to_string(), "pid: 10 digits, expected 9");
+    }
+
+    #[test]
+    fn missing_fields() {
+        let raw_passport = raw_passport("iyr:2019 hcl:#602927 eyr:2027 hgt:170cm");
+        let errors = Passport::from_raw(&raw_passport, Profile::aoc())
+            .unwrap_err()
+            .errors;
+
+        let missing_keys = errors
+            .into_iter()
+            .filter(|error| error.kind == FieldErrorKind::Missing)
+            .map(|error| error.key)
+            .collect::<Vec<String>>();
+        assert_eq!(
+            missing_keys,
+            vec![Passport::BYR, Passport::ECL, Passport::PID]
+        );
+    }
+}
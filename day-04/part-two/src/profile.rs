@@ -0,0 +1,570 @@
+use crate::passport::parse_digits;
+use crate::{FieldError, FieldErrorKind, RawPassport};
+use regex::Regex;
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+/// Set of rules which a passport must meet to be valid, e.g. rules of a
+/// single issuing country.
+#[derive(Debug, Clone)]
+pub struct Profile {
+    pub name: String,
+    fields: Vec<FieldRule>,
+}
+
+#[derive(Debug, Clone)]
+struct FieldRule {
+    key: String,
+    required: bool,
+    rule: Rule,
+}
+
+#[derive(Debug, Clone)]
+enum Rule {
+    Any,
+    IntRange {
+        min: u32,
+        max: u32,
+        digits: Option<usize>,
+    },
+    UnitRange(Vec<UnitLimits>),
+    Regex(Regex),
+    Enum(Vec<String>),
+    Digits(usize),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UnitLimits {
+    unit: String,
+    min: u32,
+    max: u32,
+}
+
+// Profile as written in the TOML file. Regexes are compiled when the file is
+// loaded, so they're not compiled again for every passport.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProfileFile {
+    name: String,
+    // Fields are read as tables first, so their keys can be checked.
+    #[serde(rename = "field")]
+    fields: Vec<toml::Table>,
+}
+
+#[derive(Deserialize)]
+struct FieldFile {
+    key: String,
+    #[serde(default)]
+    required: bool,
+    #[serde(flatten)]
+    rule: RuleFile,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum RuleFile {
+    Any,
+    IntRange {
+        min: u32,
+        max: u32,
+        digits: Option<usize>,
+    },
+    UnitRange {
+        units: Vec<UnitLimits>,
+    },
+    Regex {
+        pattern: String,
+    },
+    Enum {
+        values: Vec<String>,
+    },
+    Digits {
+        count: usize,
+    },
+}
+
+impl FieldFile {
+    /// Keys of every field, whatever its rule is.
+    const KEYS: [&'static str; 3] = ["key", "required", "type"];
+}
+
+impl RuleFile {
+    /// Keys of the rule in the profile file.
+    fn keys(&self) -> &'static [&'static str] {
+        match self {
+            RuleFile::Any => &[],
+            RuleFile::IntRange { .. } => &["min", "max", "digits"],
+            RuleFile::UnitRange { .. } => &["units"],
+            RuleFile::Regex { .. } => &["pattern"],
+            RuleFile::Enum { .. } => &["values"],
+            RuleFile::Digits { .. } => &["count"],
+        }
+    }
+}
+
+impl Profile {
+    /// Profile with the rules from the Advent of Code puzzle.
+    pub fn aoc() -> &'static Profile {
+        static AOC: OnceLock<Profile> = OnceLock::new();
+        AOC.get_or_init(|| {
+            // This `expect()` is safe because the built-in profile is
+            // checked by tests.
+            Profile::from_toml(include_str!("../profiles/aoc.toml"))
+                .expect("built-in profile must be valid")
+        })
+    }
+
+    /// Profile from the first part of the puzzle: the same fields as in
+    /// `aoc()`, but only presence of the required ones is checked.
+    pub fn presence() -> &'static Profile {
+        static PRESENCE: OnceLock<Profile> = OnceLock::new();
+        PRESENCE.get_or_init(|| Profile::aoc().presence_only())
+    }
+
+    /// Copy of the profile which only checks that required fields are
+    /// present, values of the fields are not checked.
+    pub fn presence_only(&self) -> Profile {
+        let fields = self
+            .fields
+            .iter()
+            .map(|field| FieldRule {
+                key: field.key.clone(),
+                required: field.required,
+                rule: Rule::Any,
+            })
+            .collect();
+        Profile {
+            name: format!("{} (presence only)", self.name),
+            fields,
+        }
+    }
+
+    pub fn from_toml(raw_profile: &str) -> Result<Self, String> {
+        let profile_file = toml::from_str::<ProfileFile>(raw_profile)
+            .map_err(|e| format!("unable to parse profile, {}", e))?;
+
+        let mut fields = Vec::with_capacity(profile_file.fields.len());
+        for raw_field in profile_file.fields {
+            let field = toml::Value::Table(raw_field.clone())
+                .try_into::<FieldFile>()
+                .map_err(|e| format!("unable to parse profile, {}", e))?;
+            // `deny_unknown_fields` doesn't work together with
+            // `#[serde(flatten)]`, so unknown keys, e.g. a typo like
+            // `requried`, are found here.
+            let unknown_key = raw_field.keys().find(|key| {
+                !FieldFile::KEYS.contains(&key.as_str())
+                    && !field.rule.keys().contains(&key.as_str())
+            });
+            if let Some(unknown_key) = unknown_key {
+                return Err(format!(
+                    "unknown key `{}` in field {}",
+                    unknown_key, field.key
+                ));
+            }
+            // Rules of the same key would be silently combined.
+            if fields
+                .iter()
+                .any(|earlier: &FieldRule| earlier.key == field.key)
+            {
+                return Err(format!("field {}: key is already described", field.key));
+            }
+            let check_range = |min: u32, max: u32, unit: &str| {
+                if min > max {
+                    return Err(format!(
+                        "field {}: min {}{} is greater than max {}{}",
+                        field.key, min, unit, max, unit
+                    ));
+                }
+                Ok(())
+            };
+            let rule = match field.rule {
+                RuleFile::Any => Rule::Any,
+                RuleFile::IntRange { min, max, digits } => {
+                    check_range(min, max, "")?;
+                    Rule::IntRange { min, max, digits }
+                }
+                RuleFile::UnitRange { units } => {
+                    for limits in units.iter() {
+                        check_range(limits.min, limits.max, &limits.unit)?;
+                    }
+                    Rule::UnitRange(units)
+                }
+                // Pattern must always match the whole value.
+                RuleFile::Regex { pattern } => Regex::new(&format!("^(?:{})$", pattern))
+                    .map(Rule::Regex)
+                    .map_err(|e| format!("invalid pattern for field {}, {}", field.key, e))?,
+                RuleFile::Enum { values } => Rule::Enum(values),
+                RuleFile::Digits { count } => Rule::Digits(count),
+            };
+            fields.push(FieldRule {
+                key: field.key,
+                required: field.required,
+                rule,
+            });
+        }
+        // Profile without fields would accept any passport.
+        if fields.is_empty() {
+            return Err(format!("profile {} has no fields", profile_file.name));
+        }
+        Ok(Self {
+            name: profile_file.name,
+            fields,
+        })
+    }
+
+    /// Keys of all fields which must be present in the passport.
+    pub fn required_keys(&self) -> impl Iterator<Item = &str> {
+        self.fields
+            .iter()
+            .filter(|field| field.required)
+            .map(|field| field.key.as_str())
+    }
+
+    /// Checks all fields of the passport and returns every problem found.
+    pub fn validate(&self, raw_passport: &RawPassport) -> Vec<FieldError> {
+        self.fields
+            .iter()
+            .filter_map(|field| {
+                let kind = match raw_passport.get(&field.key) {
+                    Some(value) => field.rule.check(value).err()?,
+                    None if field.required => FieldErrorKind::Missing,
+                    None => return None,
+                };
+                Some(FieldError {
+                    key: field.key.clone(),
+                    kind,
+                })
+            })
+            .collect()
+    }
+
+    /// Checks a single value of the field. Fields which are not described
+    /// by the profile accept any value.
+    pub fn accepts(&self, key: &str, value: &str) -> bool {
+        self.fields
+            .iter()
+            .filter(|field| field.key == key)
+            .all(|field| field.rule.check(value).is_ok())
+    }
+
+    /// Values allowed for the field by its `enum` rules, e.g. eye colors.
+    pub fn allowed_values<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> {
+        self.fields
+            .iter()
+            .filter(move |field| field.key == key)
+            .flat_map(|field| match &field.rule {
+                Rule::Enum(values) => values.as_slice(),
+                _ => &[],
+            })
+            .map(String::as_str)
+    }
+
+    pub fn is_valid(&self, raw_passport: &RawPassport) -> bool {
+        self.validate(raw_passport).is_empty()
+    }
+}
+
+impl Rule {
+    fn check(&self, value: &str) -> Result<(), FieldErrorKind> {
+        match self {
+            Rule::Any => Ok(()),
+            Rule::IntRange {
+                digits: Some(count),
+                ..
+            } if value.len() != *count && value.bytes().all(|symbol| symbol.is_ascii_digit()) => {
+                Err(FieldErrorKind::DigitCount {
+                    digits: value.len(),
+                    expected: *count,
+                })
+            }
+            Rule::IntRange { min, max, .. } => check_range(value, *min, *max, ""),
+            Rule::UnitRange(units) => {
+                // Value must be a number followed directly by exactly one unit.
+                let (number, limits) = units
+                    .iter()
+                    .find_map(|limits| Some((value.strip_suffix(&limits.unit)?, limits)))
+                    .ok_or_else(|| FieldErrorKind::UnknownUnit(value.to_string()))?;
+                check_range(number, limits.min, limits.max, &limits.unit)
+            }
+            Rule::Regex(re) if re.is_match(value) => Ok(()),
+            Rule::Regex(_) => Err(FieldErrorKind::InvalidFormat(value.to_string())),
+            Rule::Enum(values) if values.iter().any(|allowed| allowed == value) => Ok(()),
+            Rule::Enum(_) => Err(FieldErrorKind::UnknownValue(value.to_string())),
+            Rule::Digits(count) => {
+                if !value.chars().all(|symbol| symbol.is_ascii_digit()) {
+                    return Err(FieldErrorKind::InvalidFormat(value.to_string()));
+                }
+                if value.len() != *count {
+                    return Err(FieldErrorKind::DigitCount {
+                        digits: value.len(),
+                        expected: *count,
+                    });
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn check_range(value: &str, min: u32, max: u32, unit: &str) -> Result<(), FieldErrorKind> {
+    let number = parse_digits::<u32>(value)?;
+    if (min..=max).contains(&number) {
+        Ok(())
+    } else {
+        Err(FieldErrorKind::OutOfRange {
+            value: number,
+            min,
+            max,
+            unit: unit.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::Profile;
+    use crate::{parse_records, FieldErrorKind};
+
+    #[test]
+    fn built_in_profile() {
+        let profile = Profile::aoc();
+
+        assert_eq!(profile.name, "aoc");
+        assert_eq!(
+            profile.required_keys().collect::<Vec<&str>>(),
+            vec!["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"]
+        );
+    }
+
+    #[test]
+    fn custom_profile() {
+        let raw_profile = r#"
+            name = "north pole"
+
+            [[field]]
+            key = "byr"
+            required = true
+            type = "int_range"
+            min = 1800
+            max = 2020
+
+            [[field]]
+            key = "hgt"
+            required = true
+            type = "unit_range"
+            units = [{ unit = "mm", min = 500, max = 2500 }]
+
+            [[field]]
+            key = "ecl"
+            type = "enum"
+            values = ["red", "blk"]
+
+            [[field]]
+            key = "pid"
+            required = true
+            type = "regex"
+            pattern = "^NP[0-9]{4}$"
+        "#;
+        let profile = Profile::from_toml(raw_profile).unwrap();
+        let records = parse_records(
+            "byr:1850 hgt:1200mm pid:NP1234\n\
+             \n\
+             byr:1850 hgt:120cm ecl:brn pid:1234\n",
+        );
+
+        assert!(profile.is_valid(&records[0].passport));
+        let errors = profile.validate(&records[1].passport);
+        assert_eq!(
+            errors
+                .iter()
+                .map(|error| error.key.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["hgt", "ecl", "pid"]
+        );
+        assert_eq!(
+            errors[0].kind,
+            FieldErrorKind::UnknownUnit(String::from("120cm"))
+        );
+    }
+
+    #[test]
+    fn presence_only() {
+        let records = parse_records("byr:1850 iyr:x eyr:2020 hgt:1 hcl:2 ecl:zzz pid:3\n");
+        let profile = Profile::presence();
+
+        assert_eq!(profile.name, "aoc (presence only)");
+        assert_eq!(
+            profile.required_keys().collect::<Vec<&str>>(),
+            Profile::aoc().required_keys().collect::<Vec<&str>>()
+        );
+        assert!(profile.is_valid(&records[0].passport));
+        assert!(!Profile::aoc().is_valid(&records[0].passport));
+    }
+
+    #[test]
+    fn invalid_profile() {
+        let unknown_type = "name = \"x\"\n[[field]]\nkey = \"byr\"\ntype = \"date\"\n";
+        assert!(Profile::from_toml(unknown_type).is_err());
+
+        let invalid_pattern =
+            "name = \"x\"\n[[field]]\nkey = \"pid\"\ntype = \"regex\"\npattern = \"[0-9\"\n";
+        assert!(Profile::from_toml(invalid_pattern).is_err());
+    }
+}
+
+#[cfg(test)]
+mod adversarial {
+    use super::Profile;
+    use crate::{Passport, RawPassport};
+
+    const VALID_PASSPORT: [(&str, &str); 7] = [
+        ("byr", "1980"),
+        ("iyr", "2012"),
+        ("eyr", "2030"),
+        ("hgt", "74in"),
+        ("hcl", "#623a2f"),
+        ("ecl", "grn"),
+        ("pid", "087499704"),
+    ];
+
+    // Each case replaces a single field of a valid passport.
+    const CORPUS: [(&str, &str, bool); 58] = [
+        ("byr", "1920", true),
+        ("byr", "2002", true),
+        ("byr", "1919", false),
+        ("byr", "2003", false),
+        ("byr", "", false),
+        ("byr", "+1980", false),
+        ("byr", "-1980", false),
+        ("byr", "01980", false),
+        ("byr", "1980a", false),
+        ("byr", "0x7bc", false),
+        ("byr", "１９８０", false),
+        ("iyr", "2010", true),
+        ("iyr", "2020", true),
+        ("iyr", "2009", false),
+        ("iyr", "2021", false),
+        ("eyr", "2020", true),
+        ("eyr", "2030", true),
+        ("eyr", "2019", false),
+        ("eyr", "2031", false),
+        ("hgt", "150cm", true),
+        ("hgt", "193cm", true),
+        ("hgt", "59in", true),
+        ("hgt", "76in", true),
+        ("hgt", "149cm", false),
+        ("hgt", "194cm", false),
+        ("hgt", "58in", false),
+        ("hgt", "77in", false),
+        ("hgt", "170", false),
+        ("hgt", "cm", false),
+        ("hgt", "170cmcm", false),
+        ("hgt", "170in5cm", false),
+        ("hgt", "60incm", false),
+        ("hgt", "cm170", false),
+        ("hgt", "+170cm", false),
+        ("hgt", "170CM", false),
+        ("hcl", "#123abc", true),
+        ("hcl", "#123abz", false),
+        ("hcl", "123abc", false),
+        ("hcl", "x#123abc", false),
+        ("hcl", "#123abc0", false),
+        ("hcl", "#123ABC", false),
+        ("hcl", "##123abc", false),
+        ("hcl", "#12345", false),
+        ("ecl", "amb", true),
+        ("ecl", "oth", true),
+        ("ecl", "xambx", false),
+        ("ecl", "ambb", false),
+        ("ecl", "AMB", false),
+        ("ecl", "", false),
+        ("ecl", "wat", false),
+        ("pid", "000000001", true),
+        ("pid", "0123456789", false),
+        ("pid", "01234567", false),
+        ("pid", "12345678a", false),
+        ("pid", "+12345678", false),
+        ("pid", "", false),
+        ("pid", "١٢٣٤٥٦٧٨٩", false),
+        ("cid", "anything", true),
+    ];
+
+    fn passport_with(key: &str, value: &str) -> RawPassport {
+        VALID_PASSPORT
+            .into_iter()
+            .filter(|(valid_key, _)| *valid_key != key)
+            .chain(Some((key, value)))
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    // Each profile has a single mistake, which must not be ignored.
+    const INVALID_PROFILES: [&str; 8] = [
+        "key = \"byr\"\nrequried = true\ntype = \"int_range\"\nmin = 1920\nmax = 2002",
+        "key = \"byr\"\nrequired = true\ntype = \"int_range\"\nmin = 1920\nmaximum = 2002",
+        "key = \"pid\"\ntype = \"digits\"\ncount = 9\npattern = \"[0-9]+\"",
+        "key = \"ecl\"\ntype = \"enum\"\nvalues = [\"amb\"]\nvalue = \"blu\"",
+        "key = \"hgt\"\ntype = \"unit_range\"\nunits = [{ unit = \"cm\", min = 1, max = 2, mx = 3 }]",
+        "key = \"byr\"\ntype = \"any\"\n[[field]]\nkey = \"byr\"\ntype = \"int_range\"\nmin = 1920\nmax = 2002",
+        "key = \"byr\"\ntype = \"int_range\"\nmin = 2002\nmax = 1920",
+        "key = \"hgt\"\ntype = \"unit_range\"\nunits = [{ unit = \"cm\", min = 193, max = 150 }]",
+    ];
+
+    #[test]
+    fn invalid_profiles() {
+        for raw_field in INVALID_PROFILES {
+            let raw_profile = format!("name = \"x\"\n[[field]]\n{}\n", raw_field);
+            assert!(Profile::from_toml(&raw_profile).is_err(), "{}", raw_field);
+        }
+        assert_eq!(
+            Profile::from_toml(&format!(
+                "name = \"x\"\n[[field]]\n{}\n",
+                INVALID_PROFILES[0]
+            ))
+            .unwrap_err(),
+            "unknown key `requried` in field byr"
+        );
+        let errors = INVALID_PROFILES[5..]
+            .iter()
+            .map(|raw_field| {
+                Profile::from_toml(&format!("name = \"x\"\n[[field]]\n{}\n", raw_field))
+                    .unwrap_err()
+            })
+            .collect::<Vec<String>>();
+        assert_eq!(
+            errors,
+            vec![
+                "field byr: key is already described",
+                "field byr: min 2002 is greater than max 1920",
+                "field hgt: min 193cm is greater than max 150cm",
+            ]
+        );
+        assert_eq!(
+            Profile::from_toml("name = \"x\"\nfield = []\n").unwrap_err(),
+            "profile x has no fields"
+        );
+    }
+
+    #[test]
+    fn corpus() {
+        for (key, value, expected) in CORPUS {
+            let raw_passport = passport_with(key, value);
+
+            assert_eq!(
+                Profile::aoc().is_valid(&raw_passport),
+                expected,
+                "{}:{}",
+                key,
+                value
+            );
+            assert_eq!(
+                Passport::from_raw(&raw_passport, Profile::aoc()).is_ok(),
+                expected,
+                "{}:{}",
+                key,
+                value
+            );
+        }
+    }
+}
@@ -0,0 +1,367 @@
+use crate::{Profile, RawPassport};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, BufRead};
+
+/// Single passport from the batch file together with its position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    /// Position of the record in the batch file, numbered from 1.
+    pub index: usize,
+    /// First line of the record, numbered from 1.
+    pub first_line: usize,
+    /// Last line of the record, numbered from 1.
+    pub last_line: usize,
+    pub passport: RawPassport,
+    /// Malformed tokens found in the record. Record with any of them is
+    /// never valid.
+    pub errors: Vec<TokenError>,
+}
+
+/// Token which can't be read as a `key:value` pair.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenError {
+    /// Line of the batch file, numbered from 1.
+    pub line: usize,
+    /// Column of the batch file, numbered from 1.
+    pub column: usize,
+    pub kind: TokenErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenErrorKind {
+    MissingSeparator(String),
+    EmptyKey(String),
+    /// Key which was already used in the same record. Only the first value
+    /// is kept.
+    DuplicateKey {
+        key: String,
+        first_line: usize,
+        first_column: usize,
+    },
+}
+
+impl Record {
+    /// Checks the passport with `profile`. Record with any malformed token is
+    /// never valid. Only rules of the profile are checked, so e.g. the
+    /// `presence` profile accepts values which don't fit their types.
+    pub fn is_valid(&self, profile: &Profile) -> bool {
+        self.errors.is_empty() && profile.is_valid(&self.passport)
+    }
+}
+
+/// Collects lines of the batch file into records. Records are separated by
+/// blank lines, also lines with whitespaces only.
+#[derive(Debug, Default)]
+pub(crate) struct RecordParser {
+    records: usize,
+    lines: usize,
+    pending: Option<PendingRecord>,
+}
+
+#[derive(Debug)]
+struct PendingRecord {
+    first_line: usize,
+    last_line: usize,
+    fields: Vec<(String, String)>,
+    // Position of the first occurrence of every key.
+    positions: HashMap<String, (usize, usize)>,
+    errors: Vec<TokenError>,
+}
+
+impl RecordParser {
+    /// Reads the next line of the batch file. Returns a record if the line
+    /// closes it.
+    pub(crate) fn push_line(&mut self, line: &str) -> Option<Record> {
+        self.lines += 1;
+        // Line read from a file with Windows line endings can still end with
+        // '\r', so it's treated like any other whitespace.
+        if line.trim().is_empty() {
+            return self.finish();
+        }
+
+        let line_number = self.lines;
+        let pending = self.pending.get_or_insert_with(|| PendingRecord {
+            first_line: line_number,
+            last_line: line_number,
+            fields: Vec::new(),
+            positions: HashMap::new(),
+            errors: Vec::new(),
+        });
+        pending.last_line = line_number;
+        for (column, token) in tokens(line) {
+            pending.push_token(line_number, column, token);
+        }
+        None
+    }
+
+    /// Closes the last record, if there is any.
+    pub(crate) fn finish(&mut self) -> Option<Record> {
+        let pending = self.pending.take()?;
+        self.records += 1;
+        Some(Record {
+            index: self.records,
+            first_line: pending.first_line,
+            last_line: pending.last_line,
+            passport: pending.fields.into_iter().collect(),
+            errors: pending.errors,
+        })
+    }
+}
+
+impl PendingRecord {
+    fn push_token(&mut self, line: usize, column: usize, token: &str) {
+        let error = |kind| TokenError { line, column, kind };
+        let (key, value) = match token.split_once(':') {
+            Some(("", _)) => {
+                let error = error(TokenErrorKind::EmptyKey(token.to_string()));
+                self.errors.push(error);
+                return;
+            }
+            Some(key_value) => key_value,
+            None => {
+                let error = error(TokenErrorKind::MissingSeparator(token.to_string()));
+                self.errors.push(error);
+                return;
+            }
+        };
+
+        if let Some(&(first_line, first_column)) = self.positions.get(key) {
+            self.errors.push(error(TokenErrorKind::DuplicateKey {
+                key: key.to_string(),
+                first_line,
+                first_column,
+            }));
+            return;
+        }
+        self.positions.insert(key.to_string(), (line, column));
+        self.fields.push((key.to_string(), value.to_string()));
+    }
+}
+
+/// Splits the line into tokens separated by whitespaces. Every token comes
+/// with its column, numbered from 1.
+fn tokens(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    line.split(|symbol: char| symbol.is_ascii_whitespace())
+        .scan(0, |offset, token| {
+            let start = *offset;
+            *offset += token.len() + 1;
+            Some((start, token))
+        })
+        .filter(|(_, token)| !token.is_empty())
+        .map(move |(start, token)| (line[..start].chars().count() + 1, token))
+}
+
+/// Splits the batch file into records.
+pub fn parse_records(raw_string: &str) -> Vec<Record> {
+    let mut parser = RecordParser::default();
+    let mut records = raw_string
+        .lines()
+        .filter_map(|line| parser.push_line(line))
+        .collect::<Vec<Record>>();
+    records.extend(parser.finish());
+    records
+}
+
+/// Reads records one at a time from any buffered reader.
+///
+/// Only the current line and the current record are kept in memory, so batch
+/// files of any size can be processed. Records are the same as those returned
+/// by `parse_records` for the same data.
+#[derive(Debug)]
+pub struct RecordReader<R> {
+    reader: R,
+    parser: RecordParser,
+    line: String,
+    finished: bool,
+}
+
+impl<R: BufRead> RecordReader<R> {
+    pub fn new(reader: R) -> Self {
+        RecordReader {
+            reader,
+            parser: RecordParser::default(),
+            line: String::new(),
+            finished: false,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for RecordReader<R> {
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.finished {
+            self.line.clear();
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => {
+                    self.finished = true;
+                    return self.parser.finish().map(Ok);
+                }
+                Ok(_) => {
+                    // Line endings are removed the same way as by
+                    // `str::lines()`.
+                    let line = self.line.strip_suffix('\n').unwrap_or(&self.line);
+                    let line = line.strip_suffix('\r').unwrap_or(line);
+                    if let Some(record) = self.parser.push_line(line) {
+                        return Some(Ok(record));
+                    }
+                }
+                Err(e) => {
+                    // Nothing more can be read reliably after an error.
+                    self.finished = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+        None
+    }
+}
+
+impl fmt::Display for TokenErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TokenErrorKind::MissingSeparator(token) => {
+                write!(f, "token '{}' has no ':' between key and value", token)
+            }
+            TokenErrorKind::EmptyKey(token) => write!(f, "token '{}' has an empty key", token),
+            TokenErrorKind::DuplicateKey {
+                key,
+                first_line,
+                first_column,
+            } => write!(
+                f,
+                "key '{}' is already used at line {}, column {}",
+                key, first_line, first_column
+            ),
+        }
+    }
+}
+
+impl fmt::Display for TokenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.kind
+        )
+    }
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::{parse_records, RecordReader, TokenError, TokenErrorKind};
+    use std::io::{BufReader, ErrorKind};
+
+    #[test]
+    fn record_positions() {
+        let one_string = "ecl:gry pid:860033327\nbyr:1937\n\n\niyr:2013\n\nhcl:#cfa07d eyr:2025\n";
+        let positions = parse_records(one_string)
+            .into_iter()
+            .map(|record| (record.index, record.first_line, record.last_line))
+            .collect::<Vec<(usize, usize, usize)>>();
+
+        assert_eq!(positions, vec![(1, 1, 2), (2, 5, 5), (3, 7, 7)]);
+    }
+
+    #[test]
+    fn windows_line_endings() {
+        let one_string = "ecl:gry pid:860033327\r\nbyr:1937\r\n\r\niyr:2013\r\n";
+        let records = parse_records(one_string);
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].passport.get("pid"), Some("860033327"));
+        assert_eq!(records[0].passport.get("byr"), Some("1937"));
+        assert!(records[0].errors.is_empty());
+    }
+
+    #[test]
+    fn blank_lines_with_whitespaces() {
+        let one_string = "ecl:gry  pid:860033327 \n \t \nbyr:1937\n   \n\n\tiyr:2013";
+        let records = parse_records(one_string);
+
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].passport.get("pid"), Some("860033327"));
+        assert_eq!(records[2].passport.get("iyr"), Some("2013"));
+        assert_eq!((records[2].first_line, records[2].last_line), (6, 6));
+    }
+
+    #[test]
+    fn malformed_tokens() {
+        let one_string = "ecl:gry pid860033327\n  :1937 byr:1937\nbyr:1940 ecl:";
+        let records = parse_records(one_string);
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(
+            records[0].errors,
+            vec![
+                TokenError {
+                    line: 1,
+                    column: 9,
+                    kind: TokenErrorKind::MissingSeparator(String::from("pid860033327")),
+                },
+                TokenError {
+                    line: 2,
+                    column: 3,
+                    kind: TokenErrorKind::EmptyKey(String::from(":1937")),
+                },
+                TokenError {
+                    line: 3,
+                    column: 1,
+                    kind: TokenErrorKind::DuplicateKey {
+                        key: String::from("byr"),
+                        first_line: 2,
+                        first_column: 9,
+                    },
+                },
+                TokenError {
+                    line: 3,
+                    column: 10,
+                    kind: TokenErrorKind::DuplicateKey {
+                        key: String::from("ecl"),
+                        first_line: 1,
+                        first_column: 1,
+                    },
+                },
+            ]
+        );
+        // The first value of a duplicated key is kept.
+        assert_eq!(records[0].passport.get("byr"), Some("1937"));
+        assert_eq!(records[0].passport.get("ecl"), Some("gry"));
+        assert_eq!(
+            records[0].errors[0].to_string(),
+            "line 1, column 9: token 'pid860033327' has no ':' between key and value"
+        );
+    }
+
+    #[test]
+    fn streaming_reader() {
+        let inputs = [
+            "ecl:gry pid:860033327\nbyr:1937\n\n\niyr:2013\n\nhcl:#cfa07d eyr:2025\n",
+            "ecl:gry pid:860033327\r\nbyr:1937\r\n\r\niyr:2013\r\n",
+            "ecl:gry  pid:860033327 \n \t \nbyr:1937\n   \n\n\tiyr:2013",
+            "ecl:gry pid860033327\n  :1937 byr:1937\nbyr:1940 ecl:",
+            "\n\n",
+            "",
+        ];
+        for input in inputs {
+            // Tiny buffer makes the reader refill it in the middle of lines.
+            let reader = BufReader::with_capacity(3, input.as_bytes());
+            let records = RecordReader::new(reader)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(records, parse_records(input), "input {:?}", input);
+        }
+    }
+
+    #[test]
+    fn streaming_reader_invalid_data() {
+        let input: &[u8] = b"ecl:gry\n\npid:\xff\n\nbyr:1937\n";
+        let mut reader = RecordReader::new(input);
+
+        assert_eq!(reader.next().unwrap().unwrap().index, 1);
+        let error = reader.next().unwrap().unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert!(reader.next().is_none());
+    }
+}
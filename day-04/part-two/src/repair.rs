@@ -0,0 +1,322 @@
+use crate::{parse_records, Passport, Profile, Record};
+use std::fmt;
+
+/// Likely fix of a single field of an invalid passport.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Repair {
+    /// Position of the record in the batch file, numbered from 1.
+    pub record: usize,
+    pub key: String,
+    pub from: String,
+    pub to: String,
+    pub kind: RepairKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RepairKind {
+    /// Height without unit, which fits the range of only one unit.
+    HeightUnit(String),
+    /// Passport ID with a doubled digit or a leading zero too many.
+    StrayDigit,
+    /// Hair color written with upper-case hex digits.
+    UppercaseHex,
+    /// Eye color which differs by a single letter from exactly one allowed
+    /// color.
+    EyeColorTypo,
+}
+
+/// Suggests fixes of all invalid fields of the record which have exactly one
+/// likely fix. Every fix is checked with `profile`, fields which are valid
+/// already are never changed.
+pub fn suggest_repairs(record: &Record, profile: &Profile) -> Vec<Repair> {
+    let mut repairs = Vec::new();
+    for (key, value) in record.passport.fields() {
+        if profile.accepts(key, value) {
+            continue;
+        }
+        let (candidates, kind) = match key {
+            Passport::HGT => {
+                let candidates = ["cm", "in"]
+                    .into_iter()
+                    .map(|unit| format!("{}{}", value, unit))
+                    .filter(|candidate| profile.accepts(key, candidate))
+                    .collect::<Vec<String>>();
+                // The unit is known only if there is exactly one candidate.
+                let unit = match &candidates[..] {
+                    [candidate] => candidate[value.len()..].to_string(),
+                    _ => continue,
+                };
+                (candidates, RepairKind::HeightUnit(unit))
+            }
+            Passport::PID => (stray_digit_candidates(value), RepairKind::StrayDigit),
+            Passport::HCL => (vec![value.to_ascii_lowercase()], RepairKind::UppercaseHex),
+            // Allowed colors come from the profile, which may allow other
+            // colors than the puzzle.
+            Passport::ECL => (
+                profile
+                    .allowed_values(key)
+                    .filter(|code| edit_distance(code, value) == 1)
+                    .map(String::from)
+                    .collect(),
+                RepairKind::EyeColorTypo,
+            ),
+            _ => continue,
+        };
+
+        let candidates = candidates
+            .into_iter()
+            .filter(|candidate| candidate != value && profile.accepts(key, candidate))
+            .collect::<Vec<String>>();
+        // Ambiguous fixes are not suggested, only a human can choose one of
+        // them.
+        if let [candidate] = &candidates[..] {
+            repairs.push(Repair {
+                record: record.index,
+                key: key.to_string(),
+                from: value.to_string(),
+                to: candidate.clone(),
+                kind,
+            });
+        }
+    }
+    repairs
+}
+
+/// Batch file with all suggested fixes applied, together with the list of
+/// the applied fixes. Only the fixed values are changed, everything else,
+/// including malformed tokens and layout of the records, is copied as it is,
+/// so nothing is changed without an entry in the change log.
+///
+/// Records with malformed tokens are not repaired: they stay invalid
+/// whatever is fixed, and a value of a duplicate key may come from another
+/// token than the one which would be fixed.
+pub fn repair(raw_data: &str, profile: &Profile) -> (String, Vec<Repair>) {
+    let records = parse_records(raw_data);
+    let change_log = records
+        .iter()
+        .filter(|record| record.errors.is_empty() && !record.is_valid(profile))
+        .flat_map(|record| suggest_repairs(record, profile))
+        .collect::<Vec<Repair>>();
+
+    // Records are numbered from 1 in the order of the batch file.
+    let mut repairs_by_record = vec![Vec::new(); records.len()];
+    for repair in change_log.iter() {
+        repairs_by_record[repair.record - 1].push(repair);
+    }
+
+    let mut repaired_data = String::with_capacity(raw_data.len());
+    // Records are walked together with the lines, which are numbered the
+    // same way as by `parse_records()`.
+    let mut records = records.iter().peekable();
+    for (i, line) in raw_data.split_inclusive('\n').enumerate() {
+        let line_number = i + 1;
+        while records
+            .next_if(|record| record.last_line < line_number)
+            .is_some()
+        {}
+        let repairs = match records.peek() {
+            Some(record) if record.first_line <= line_number => {
+                &repairs_by_record[record.index - 1][..]
+            }
+            _ => &[],
+        };
+        repaired_data.push_str(&replace_tokens(line, repairs));
+    }
+    (repaired_data, change_log)
+}
+
+/// Replaces the fixed `key:value` tokens of the line, whitespaces are kept.
+fn replace_tokens(line: &str, repairs: &[&Repair]) -> String {
+    line.split_inclusive(|symbol: char| symbol.is_ascii_whitespace())
+        .map(|part| {
+            let token = part.trim_end_matches(|symbol: char| symbol.is_ascii_whitespace());
+            let repair = repairs.iter().find(|repair| {
+                token.split_once(':') == Some((repair.key.as_str(), repair.from.as_str()))
+            });
+            match repair {
+                Some(repair) => format!("{}:{}{}", repair.key, repair.to, &part[token.len()..]),
+                None => part.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Values with one digit removed, where the removed digit is the same as the
+/// next one or is a leading zero.
+fn stray_digit_candidates(value: &str) -> Vec<String> {
+    if !value.bytes().all(|symbol| symbol.is_ascii_digit()) {
+        return Vec::new();
+    }
+    let bytes = value.as_bytes();
+    let mut candidates = Vec::new();
+    for i in 0..bytes.len() {
+        let doubled = bytes.get(i + 1) == Some(&bytes[i]);
+        let leading_zero = i == 0 && bytes[i] == b'0';
+        if doubled || leading_zero {
+            let candidate = format!("{}{}", &value[..i], &value[i + 1..]);
+            if !candidates.contains(&candidate) {
+                candidates.push(candidate);
+            }
+        }
+    }
+    candidates
+}
+
+/// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<char>>();
+    let mut previous = (0..=b.len()).collect::<Vec<usize>>();
+    for (i, a_symbol) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_symbol) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_symbol != *b_symbol);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+impl fmt::Display for RepairKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RepairKind::HeightUnit(unit) => {
+                write!(f, "height without unit fits only the {} range", unit)
+            }
+            RepairKind::StrayDigit => write!(f, "stray digit removed"),
+            RepairKind::UppercaseHex => write!(f, "hex digits changed to lower case"),
+            RepairKind::EyeColorTypo => write!(f, "typo in eye color"),
+        }
+    }
+}
+
+impl fmt::Display for Repair {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "passport {}: {} '{}' -> '{}' ({})",
+            self.record, self.key, self.from, self.to, self.kind
+        )
+    }
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::{edit_distance, repair, suggest_repairs, RepairKind};
+    use crate::{parse_records, Profile};
+
+    fn kinds(one_string: &str) -> Vec<(String, String, RepairKind)> {
+        let records = parse_records(one_string);
+        suggest_repairs(&records[0], Profile::aoc())
+            .into_iter()
+            .map(|repair| (repair.key, repair.to, repair.kind))
+            .collect()
+    }
+
+    #[test]
+    fn height_unit() {
+        assert_eq!(
+            kinds("hgt:170"),
+            vec![(
+                String::from("hgt"),
+                String::from("170cm"),
+                RepairKind::HeightUnit(String::from("cm"))
+            )]
+        );
+        assert_eq!(kinds("hgt:65")[0].1, "65in");
+        // Fits none of the ranges.
+        assert_eq!(kinds("hgt:100"), vec![]);
+    }
+
+    #[test]
+    fn stray_digit() {
+        assert_eq!(kinds("pid:0123456789")[0].1, "123456789");
+        assert_eq!(kinds("pid:1233456789")[0].1, "123456789");
+        // Two possible fixes.
+        assert_eq!(kinds("pid:1123456788"), vec![]);
+        assert_eq!(kinds("pid:1234567890"), vec![]);
+    }
+
+    #[test]
+    fn uppercase_hex() {
+        assert_eq!(kinds("hcl:#623A2F")[0].1, "#623a2f");
+        assert_eq!(kinds("hcl:623A2F"), vec![]);
+    }
+
+    #[test]
+    fn eye_color_typo() {
+        assert_eq!(kinds("ecl:amg")[0].1, "amb");
+        assert_eq!(kinds("ecl:hz")[0].1, "hzl");
+        // Both `grn` and `gry` are one letter away.
+        assert_eq!(kinds("ecl:grx"), vec![]);
+        assert_eq!(kinds("ecl:zzz"), vec![]);
+    }
+
+    #[test]
+    fn custom_eye_colors() {
+        let profile = Profile::from_toml(
+            "name = \"x\"\n[[field]]\nkey = \"ecl\"\nrequired = true\n\
+             type = \"enum\"\nvalues = [\"red\", \"pnk\"]\n",
+        )
+        .unwrap();
+        let suggestions = |one_string: &str| {
+            suggest_repairs(&parse_records(one_string)[0], &profile)
+                .into_iter()
+                .map(|repair| repair.to)
+                .collect::<Vec<String>>()
+        };
+
+        assert_eq!(suggestions("ecl:rex"), vec![String::from("red")]);
+        // `amb` is allowed only by the puzzle.
+        assert_eq!(suggestions("ecl:amg"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn repaired_dataset() {
+        let raw_data = "pid:087499704 hgt:74 ecl:bly iyr:2012\teyr:2030\r\n\
+                        byr:1980  hcl:#623A2F\r\n\
+                        \r\n\
+                        iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719\n\
+                        \n\
+                        iyr:2010 hgt:158 hcl:#b6652a ecl:blu byr:1944 eyr2021 pid:093154719\n\
+                        \n\
+                        ecl:blx ecl:bly\n\
+                        \n\
+                        hgt:74 hcl:#ABCDEF\n";
+        let (repaired_data, change_log) = repair(raw_data, Profile::aoc());
+
+        assert_eq!(change_log.len(), 5);
+        assert_eq!(change_log[4].record, 5);
+        assert_eq!(
+            change_log[0].to_string(),
+            "passport 1: ecl 'bly' -> 'blu' (typo in eye color)"
+        );
+        // Records with malformed tokens, e.g. a missing `:` or a duplicate
+        // key, are copied unchanged.
+        assert_eq!(
+            repaired_data,
+            "pid:087499704 hgt:74in ecl:blu iyr:2012\teyr:2030\r\n\
+             byr:1980  hcl:#623a2f\r\n\
+             \r\n\
+             iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719\n\
+             \n\
+             iyr:2010 hgt:158 hcl:#b6652a ecl:blu byr:1944 eyr2021 pid:093154719\n\
+             \n\
+             ecl:blx ecl:bly\n\
+             \n\
+             hgt:74in hcl:#abcdef\n"
+        );
+        let records = parse_records(&repaired_data);
+        assert_eq!(records.len(), 5);
+        assert!(records[0].is_valid(Profile::aoc()));
+        assert!(!records[2].is_valid(Profile::aoc()));
+    }
+
+    #[test]
+    fn distance() {
+        assert_eq!(edit_distance("amb", "amb"), 0);
+        assert_eq!(edit_distance("amb", "am"), 1);
+        assert_eq!(edit_distance("amb", "bam"), 2);
+        assert_eq!(edit_distance("", "oth"), 3);
+    }
+}
@@ -0,0 +1,162 @@
+use crate::{FieldError, Profile, Record};
+use serde_json::json;
+
+/// All problems found in a single record of the batch file. Malformed
+/// tokens are stored in the record itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic<'a> {
+    pub record: &'a Record,
+    pub errors: Vec<FieldError>,
+}
+
+impl Diagnostic<'_> {
+    pub fn is_valid(&self) -> bool {
+        self.record.errors.is_empty() && self.errors.is_empty()
+    }
+}
+
+pub fn diagnose<'a>(records: &'a [Record], profile: &Profile) -> Vec<Diagnostic<'a>> {
+    records
+        .iter()
+        .map(|record| Diagnostic {
+            record,
+            errors: profile.validate(&record.passport),
+        })
+        .collect()
+}
+
+/// Human-readable report, with one failure per line.
+pub fn text_report(diagnostics: &[Diagnostic]) -> String {
+    let mut report = String::new();
+    for diagnostic in diagnostics {
+        let status = if diagnostic.is_valid() {
+            "valid"
+        } else {
+            "invalid"
+        };
+        report.push_str(&format!(
+            "passport {} (lines {}-{}): {}\n",
+            diagnostic.record.index,
+            diagnostic.record.first_line,
+            diagnostic.record.last_line,
+            status
+        ));
+        for error in diagnostic.record.errors.iter() {
+            report.push_str(&format!("  {}\n", error));
+        }
+        for error in diagnostic.errors.iter() {
+            report.push_str(&format!("  {}\n", error));
+        }
+    }
+    report
+}
+
+/// Report as a JSON array, with one object per passport.
+pub fn json_report(diagnostics: &[Diagnostic]) -> String {
+    let passports = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let errors = diagnostic
+                .errors
+                .iter()
+                .map(|error| json!({ "field": error.key, "message": error.kind.to_string() }))
+                .collect::<Vec<serde_json::Value>>();
+            let token_errors = diagnostic
+                .record
+                .errors
+                .iter()
+                .map(|error| {
+                    json!({
+                        "line": error.line,
+                        "column": error.column,
+                        "message": error.kind.to_string(),
+                    })
+                })
+                .collect::<Vec<serde_json::Value>>();
+            let fields = diagnostic
+                .record
+                .passport
+                .fields()
+                .into_iter()
+                .map(|(key, value)| json!({ "key": key, "value": value }))
+                .collect::<Vec<serde_json::Value>>();
+            json!({
+                "passport": diagnostic.record.index,
+                "first_line": diagnostic.record.first_line,
+                "last_line": diagnostic.record.last_line,
+                "valid": diagnostic.is_valid(),
+                "fields": fields,
+                "token_errors": token_errors,
+                "errors": errors,
+            })
+        })
+        .collect::<Vec<serde_json::Value>>();
+    serde_json::Value::Array(passports).to_string()
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::{diagnose, json_report, text_report};
+    use crate::{parse_records, Profile};
+
+    const LINES: [&str; 6] = [
+        "hgt:59cm ecl:zzz\n",
+        "eyr:2028 hcl:#74454a iyr:2013\n",
+        "pid:3556412378 byr:2001\n",
+        "\n",
+        "iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021\n",
+        "pid:093154719",
+    ];
+
+    #[test]
+    fn text() {
+        let records = parse_records(&LINES.concat());
+        let report = text_report(&diagnose(&records, Profile::aoc()));
+
+        assert_eq!(
+            report,
+            "passport 1 (lines 1-3): invalid\n\
+             \x20 hgt: 59cm below minimum 150cm\n\
+             \x20 ecl: 'zzz' is not an allowed value\n\
+             \x20 pid: 10 digits, expected 9\n\
+             passport 2 (lines 5-6): valid\n"
+        );
+    }
+
+    #[test]
+    fn json() {
+        let records = parse_records(&LINES.concat());
+        let report = json_report(&diagnose(&records, Profile::aoc()));
+        let report = serde_json::from_str::<serde_json::Value>(&report).unwrap();
+
+        assert_eq!(report[0]["valid"], false);
+        assert_eq!(report[0]["first_line"], 1);
+        assert_eq!(report[0]["last_line"], 3);
+        assert_eq!(report[0]["errors"][0]["field"], "hgt");
+        assert_eq!(
+            report[0]["errors"][0]["message"],
+            "59cm below minimum 150cm"
+        );
+        assert_eq!(report[0]["fields"][0]["key"], "byr");
+        assert_eq!(report[0]["fields"][0]["value"], "2001");
+        assert_eq!(report[0]["fields"].as_array().unwrap().len(), 7);
+        assert_eq!(report[0]["token_errors"].as_array().unwrap().len(), 0);
+        assert_eq!(report[1]["valid"], true);
+        assert_eq!(report[1]["errors"].as_array().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn malformed_tokens() {
+        let records = parse_records(
+            "iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021\r\n\
+             pid:093154719 eyr2021\r\n",
+        );
+        let report = text_report(&diagnose(&records, Profile::aoc()));
+
+        assert_eq!(
+            report,
+            "passport 1 (lines 1-2): invalid\n\
+             \x20 line 2, column 15: token 'eyr2021' has no ':' between key and value\n"
+        );
+    }
+}
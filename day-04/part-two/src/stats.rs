@@ -0,0 +1,369 @@
+use crate::{FieldErrorKind, Height, PartialPassport, Passport, Profile, Record};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Width of the longest bar in the dashboard.
+const BAR_WIDTH: usize = 40;
+/// Number of the most common eye colors shown in the dashboard, the rest is
+/// summed up in a single line.
+const TOP_EYE_COLORS: usize = 10;
+
+/// Aggregate statistics of a whole batch file.
+///
+/// Statistics are collected one record at a time, so they can be built from
+/// a `RecordReader` without keeping the records in memory.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Statistics {
+    pub passports: usize,
+    pub valid_passports: usize,
+    /// Number of passports with malformed tokens.
+    pub malformed_passports: usize,
+    /// Number of passports without the field, for every known key.
+    pub missing_fields: BTreeMap<String, usize>,
+    /// Number of passports failing the rule of the field, missing required
+    /// fields included.
+    pub rule_failures: BTreeMap<String, usize>,
+    /// Number of passports with each birth year which is a number.
+    pub birth_years: BTreeMap<u16, usize>,
+    /// Number of passports with each height in centimeters.
+    pub heights_cm: BTreeMap<u8, usize>,
+    /// Number of passports with each height in inches.
+    pub heights_in: BTreeMap<u8, usize>,
+    /// Number of passports with a height without a known unit.
+    pub heights_without_unit: usize,
+    /// Number of passports with a height with a known unit, which is not a
+    /// valid number, e.g. `300cm` or `abccm`.
+    pub invalid_heights: usize,
+    /// Number of passports with each eye color, also the not allowed ones.
+    pub eye_colors: BTreeMap<String, usize>,
+    // Indexes of records with every passport ID.
+    passport_ids: HashMap<String, Vec<usize>>,
+}
+
+impl Statistics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a single record to the statistics. Validity of the passport is
+    /// checked with `profile`.
+    pub fn add(&mut self, record: &Record, profile: &Profile) {
+        let passport = PartialPassport::from(&record.passport);
+        let errors = profile.validate(&record.passport);
+
+        self.passports += 1;
+        if !record.errors.is_empty() {
+            self.malformed_passports += 1;
+        }
+        if record.errors.is_empty() && errors.is_empty() {
+            self.valid_passports += 1;
+        }
+
+        for key in Passport::KEYS {
+            let missing = self.missing_fields.entry(key.to_string()).or_insert(0);
+            if !record.passport.contains_key(key) {
+                *missing += 1;
+            }
+        }
+        for error in errors {
+            *self.rule_failures.entry(error.key).or_insert(0) += 1;
+        }
+
+        if let Some(year) = passport.birth_year {
+            *self.birth_years.entry(year).or_insert(0) += 1;
+        }
+        match passport.height {
+            Some(Height::Cm(height)) => *self.heights_cm.entry(height).or_insert(0) += 1,
+            Some(Height::In(height)) => *self.heights_in.entry(height).or_insert(0) += 1,
+            // Values which don't fit the type are anomalies of the batch, so
+            // the reason is found from the value as it's written.
+            None => match record
+                .passport
+                .get(Passport::HGT)
+                .map(Passport::parse_height)
+            {
+                Some(Err(FieldErrorKind::UnknownUnit(_))) => self.heights_without_unit += 1,
+                Some(_) => self.invalid_heights += 1,
+                None => {}
+            },
+        }
+        if let Some(eye_color) = record.passport.get(Passport::ECL) {
+            *self.eye_colors.entry(eye_color.to_string()).or_insert(0) += 1;
+        }
+        // Only well-formed IDs are compared, a value in a wrong field isn't a
+        // real duplicate.
+        if let Some(passport_id) = passport.passport_id {
+            let passport_id = passport_id
+                .iter()
+                .map(|digit| char::from(b'0' + digit))
+                .collect::<String>();
+            self.passport_ids
+                .entry(passport_id)
+                .or_default()
+                .push(record.index);
+        }
+    }
+
+    /// Share of passports failing the rule of the field, from 0 to 1.
+    pub fn failure_share(&self, key: &str) -> f64 {
+        let failures = self.rule_failures.get(key).copied().unwrap_or(0);
+        share(failures, self.passports)
+    }
+
+    /// Passport IDs used by more than one passport, with indexes of the
+    /// records which use them.
+    pub fn duplicate_passport_ids(&self) -> Vec<(&str, &[usize])> {
+        let mut duplicates = self
+            .passport_ids
+            .iter()
+            .filter(|(_, indexes)| indexes.len() > 1)
+            .map(|(passport_id, indexes)| (passport_id.as_str(), indexes.as_slice()))
+            .collect::<Vec<(&str, &[usize])>>();
+        duplicates.sort_unstable();
+        duplicates
+    }
+}
+
+pub fn statistics(records: &[Record], profile: &Profile) -> Statistics {
+    let mut statistics = Statistics::new();
+    for record in records {
+        statistics.add(record, profile);
+    }
+    statistics
+}
+
+fn share(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    count as f64 / total as f64
+}
+
+/// Writes one line of a chart: label, count, percentage of all passports
+/// and a bar scaled to the largest count of the chart.
+fn write_bar(
+    f: &mut fmt::Formatter,
+    label: &str,
+    count: usize,
+    total: usize,
+    max_count: usize,
+) -> fmt::Result {
+    let bar_length = if max_count == 0 {
+        0
+    } else {
+        // Every non-zero count gets at least one mark.
+        (count * BAR_WIDTH).div_ceil(max_count)
+    };
+    writeln!(
+        f,
+        "  {:<8} {:>6} {:>6.1}% {}",
+        label,
+        count,
+        100.0 * share(count, total),
+        "#".repeat(bar_length)
+    )
+}
+
+fn write_chart<I>(f: &mut fmt::Formatter, title: &str, rows: I, total: usize) -> fmt::Result
+where
+    I: IntoIterator<Item = (String, usize)>,
+    I::IntoIter: Clone,
+{
+    let rows = rows.into_iter();
+    writeln!(f, "{}", title)?;
+    let max_count = rows.clone().map(|(_, count)| count).max().unwrap_or(0);
+    if max_count == 0 {
+        return writeln!(f, "  none");
+    }
+    for (label, count) in rows {
+        write_bar(f, &label, count, total, max_count)?;
+    }
+    Ok(())
+}
+
+/// Groups values into buckets of `width` labeled with the first value of the
+/// bucket, e.g. `1920s` for birth years.
+fn buckets<T>(values: &BTreeMap<T, usize>, width: u32, suffix: &str) -> Vec<(String, usize)>
+where
+    T: Copy + Into<u32>,
+{
+    let mut buckets = BTreeMap::new();
+    for (&value, &count) in values {
+        let bucket = value.into() / width * width;
+        *buckets.entry(bucket).or_insert(0) += count;
+    }
+    buckets
+        .into_iter()
+        .map(|(bucket, count)| (format!("{}{}", bucket, suffix), count))
+        .collect()
+}
+
+impl fmt::Display for Statistics {
+    /// Dashboard with all statistics, for the terminal.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let total = self.passports;
+        writeln!(
+            f,
+            "Passports: {} (valid {}, invalid {}, with malformed tokens {})",
+            total,
+            self.valid_passports,
+            total - self.valid_passports,
+            self.malformed_passports
+        )?;
+
+        writeln!(f)?;
+        let missing_fields = Passport::KEYS.iter().map(|key| {
+            let count = self.missing_fields.get(*key).copied().unwrap_or(0);
+            (key.to_string(), count)
+        });
+        write_chart(f, "Missing fields", missing_fields, total)?;
+
+        writeln!(f)?;
+        let rule_failures = self
+            .rule_failures
+            .iter()
+            .map(|(key, &count)| (key.clone(), count));
+        write_chart(f, "Rule failures", rule_failures, total)?;
+
+        writeln!(f)?;
+        write_chart(f, "Birth years", buckets(&self.birth_years, 10, "s"), total)?;
+
+        writeln!(f)?;
+        let heights_cm = self.heights_cm.values().sum::<usize>();
+        let heights_in = self.heights_in.values().sum::<usize>();
+        let units = vec![
+            (String::from("cm"), heights_cm),
+            (String::from("in"), heights_in),
+            (String::from("no unit"), self.heights_without_unit),
+            (String::from("invalid"), self.invalid_heights),
+        ];
+        write_chart(f, "Heights by unit", units, total)?;
+        write_chart(
+            f,
+            "Heights in centimeters",
+            buckets(&self.heights_cm, 10, "cm"),
+            heights_cm,
+        )?;
+        write_chart(
+            f,
+            "Heights in inches",
+            buckets(&self.heights_in, 5, "in"),
+            heights_in,
+        )?;
+
+        writeln!(f)?;
+        let mut eye_colors = self
+            .eye_colors
+            .iter()
+            .map(|(eye_color, &count)| (eye_color.clone(), count))
+            .collect::<Vec<(String, usize)>>();
+        // The most common colors first.
+        eye_colors.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        if eye_colors.len() > TOP_EYE_COLORS {
+            let rest = eye_colors.split_off(TOP_EYE_COLORS);
+            let count = rest.iter().map(|(_, count)| count).sum();
+            eye_colors.push((format!("{} others", rest.len()), count));
+        }
+        write_chart(f, "Eye colors", eye_colors, total)?;
+
+        writeln!(f)?;
+        writeln!(f, "Duplicate passport IDs")?;
+        let duplicates = self.duplicate_passport_ids();
+        if duplicates.is_empty() {
+            writeln!(f, "  none")?;
+        }
+        for (passport_id, indexes) in duplicates {
+            let indexes = indexes
+                .iter()
+                .map(|index| index.to_string())
+                .collect::<Vec<String>>();
+            writeln!(f, "  {}: passports {}", passport_id, indexes.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod example_data {
+    use super::statistics;
+    use crate::{parse_records, Profile};
+
+    const LINES: [&str; 14] = [
+        "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd\n",
+        "byr:1937 iyr:2017 cid:147 hgt:183cm\n",
+        "\n",
+        "iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884\n",
+        "hcl:#cfa07d byr:1929\n",
+        "\n",
+        "hcl:#ae17e1 iyr:2013\n",
+        "eyr:2024\n",
+        "ecl:brn pid:760753108 byr:1931\n",
+        "hgt:179cm\n",
+        "\n",
+        "hcl:#cfa07d eyr:2025 pid:860033327 iyr:2011\n",
+        "ecl:zzz hgt:59in\n",
+        "byr:1985",
+    ];
+
+    #[test]
+    fn batch_statistics() {
+        let records = parse_records(&LINES.concat());
+        let statistics = statistics(&records, Profile::aoc());
+
+        assert_eq!(statistics.passports, 4);
+        assert_eq!(statistics.valid_passports, 2);
+        assert_eq!(statistics.malformed_passports, 0);
+        assert_eq!(statistics.missing_fields["hgt"], 1);
+        assert_eq!(statistics.missing_fields["cid"], 2);
+        assert_eq!(statistics.missing_fields["byr"], 0);
+        assert_eq!(statistics.rule_failures["hgt"], 1);
+        assert_eq!(statistics.rule_failures["ecl"], 1);
+        assert_eq!(statistics.rule_failures.get("cid"), None);
+        assert_eq!(statistics.failure_share("hgt"), 0.25);
+        assert_eq!(statistics.failure_share("byr"), 0.0);
+        assert_eq!(
+            statistics.birth_years.keys().copied().collect::<Vec<u16>>(),
+            vec![1929, 1931, 1937, 1985]
+        );
+        assert_eq!(statistics.heights_cm.values().sum::<usize>(), 2);
+        assert_eq!(statistics.heights_in.get(&59), Some(&1));
+        assert_eq!(statistics.eye_colors["zzz"], 1);
+        assert_eq!(
+            statistics.duplicate_passport_ids(),
+            vec![("860033327", &[1, 4][..])]
+        );
+    }
+
+    #[test]
+    fn heights() {
+        let records = parse_records("hgt:170\n\nhgt:300cm\n\nhgt:abccm\n\nhgt:170cm\n\nhgt:5ft\n");
+        let statistics = statistics(&records, Profile::aoc());
+
+        assert_eq!(statistics.heights_cm.get(&170), Some(&1));
+        assert_eq!(statistics.heights_without_unit, 2);
+        assert_eq!(statistics.invalid_heights, 2);
+        assert!(statistics.to_string().contains(
+            "  no unit       2   40.0% ########################################\n\
+             \x20 invalid       2   40.0% ########################################\n"
+        ));
+    }
+
+    #[test]
+    fn dashboard() {
+        let records = parse_records(&LINES.concat());
+        let dashboard = statistics(&records, Profile::aoc()).to_string();
+
+        assert!(
+            dashboard.starts_with("Passports: 4 (valid 2, invalid 2, with malformed tokens 0)\n")
+        );
+        assert!(dashboard.contains(
+            "Missing fields\n\
+             \x20 byr           0    0.0% \n"
+        ));
+        assert!(dashboard
+            .contains("\x20 cid           2   50.0% ########################################\n"));
+        assert!(dashboard
+            .contains("  1930s         2   50.0% ########################################\n"));
+        assert!(dashboard.contains("Duplicate passport IDs\n  860033327: passports 1, 4\n"));
+    }
+}
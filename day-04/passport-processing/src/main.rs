@@ -1,129 +1,38 @@
-use std::collections::HashSet;
-use std::env;
-use std::path::Path;
+use aoc_common::{phase, Args, Error};
+use part_two::{count_valid_passports_with_profile, Profile};
+use std::process::ExitCode;
 
-struct Passport(HashSet<String>);
+const USAGE: &str = "Program must be executed with one argument: [input]";
 
-fn main() {
-    let args = env::args().collect::<Vec<String>>();
-    if args.len() != 2 {
-        eprintln!("Program must be executed with one argument: [file_name]");
-        return;
-    }
-
-    // First argument is name of binary file.
-    // Usefully is second argument which is `file name` with data.
-    let file_name = match args.get(1) {
-        Some(name) => name,
-        None => {
-            eprintln!("unable to get file name");
-            return;
-        }
-    };
-    let path = Path::new(file_name);
-    let raw_data_file = match std::fs::read_to_string(path) {
-        Ok(data) => data,
-        Err(e) => {
-            eprintln!("unable to read data from file, {}", e);
-            return;
-        }
-    };
-
-    let valid_passports = count_valid_passports(raw_data_file);
-    println!("Valid passports: {}", valid_passports);
-}
-
-fn count_valid_passports(raw_string: String) -> usize {
-    raw_string
-        .split("\n\n")
-        .map(|raw_data| {
-            raw_data
-                .split_ascii_whitespace()
-                .map(|key_value| key_value.split(':').next().unwrap().to_string())
-                .collect::<Passport>()
-        })
-        .filter(Passport::is_valid)
-        .count()
+fn main() -> ExitCode {
+    aoc_common::run(solve)
 }
 
-impl Passport {
-    /// Key for `birth year`.
-    const BYR: &'static str = "byr";
-    /// Key for `issue year`.
-    const IYR: &'static str = "iyr";
-    /// Key for `expiration year`.
-    const EYR: &'static str = "eyr";
-    /// Key for `height`.
-    const HGT: &'static str = "hgt";
-    /// Key for `hair color`.
-    const HCL: &'static str = "hcl";
-    /// Key for `eye color`.
-    const ECL: &'static str = "ecl";
-    /// Key for `passport ID`.
-    const PID: &'static str = "pid";
-    /// Key for `country ID`.
-    #[allow(dead_code)]
-    const CID: &'static str = "cid";
+fn solve() -> Result<(), Error> {
+    let args = Args::from_env(USAGE, 0..=0)?;
+    let raw_data_file = phase("parse", || args.input.text())?;
 
-    const REQUIRED_KEYS: [&'static str; 7] = [
-        Self::BYR,
-        Self::IYR,
-        Self::EYR,
-        Self::HGT,
-        Self::HCL,
-        Self::ECL,
-        Self::PID,
-        // Field `cid` is not necessary - it's optional.
-    ];
-
-    pub fn is_valid(&self) -> bool {
-        Self::REQUIRED_KEYS
-            .into_iter()
-            .all(|key| self.0.contains(key))
-    }
+    let valid_passports = phase("solve", || count_valid_passports(&raw_data_file));
+    println!("Valid passports: {}", valid_passports);
+    Ok(())
 }
 
-impl FromIterator<String> for Passport {
-    fn from_iter<T>(iter: T) -> Self
-    where
-        T: IntoIterator<Item = String>,
-    {
-        let mut keys = HashSet::new();
-        for key in iter {
-            keys.insert(key);
-        }
-        Passport(keys)
-    }
+/// Counts passports with all required fields. Parsing and the list of
+/// required fields are shared with the second part, see `--level` option of
+/// `part-two` to get answers of both parts at once.
+fn count_valid_passports(raw_string: &str) -> usize {
+    count_valid_passports_with_profile(raw_string, Profile::presence())
 }
 
 #[cfg(test)]
 mod example_data {
     use super::count_valid_passports;
 
+    const PASSPORTS: &str = include_str!("../../../examples/day04/passports.txt");
+
     #[test]
     fn example_data() {
-        // Simulates data saved in file.
-        // Each line is ended with newline ('\n').
-        let lines = vec![
-            "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd\n",
-            "byr:1937 iyr:2017 cid:147 hgt:183cm\n",
-            "\n",
-            "iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884\n",
-            "hcl:#cfa07d byr:1929\n",
-            "\n",
-            "hcl:#ae17e1 iyr:2013\n",
-            "eyr:2024\n",
-            "ecl:brn pid:760753108 byr:1931\n",
-            "hgt:179cm\n",
-            "\n",
-            "hcl:#cfa07d eyr:2025 pid:166559648\n",
-            "iyr:2011 ecl:brn hgt:59in\n",
-        ];
-        let mut one_string = String::new();
-        for line in lines {
-            one_string.push_str(line);
-        }
-        let valid_passports = count_valid_passports(one_string);
+        let valid_passports = count_valid_passports(PASSPORTS);
         assert_eq!(valid_passports, 2);
     }
 }